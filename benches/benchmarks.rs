@@ -1,5 +1,6 @@
 use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
 use std::hint::black_box;
+use std::sync::atomic::{AtomicBool, Ordering};
 
 use tincan::{Memo, Signal, Store};
 
@@ -34,6 +35,38 @@ fn signal_write_benchmark(c: &mut Criterion) {
     });
 }
 
+/// Readers contending with a writer; compare with `--features arc-swap`.
+fn signal_concurrent_read_benchmark(c: &mut Criterion) {
+    let signal: Signal<Vec<u64>> = Signal::new(vec![0; 64]);
+    let writing = AtomicBool::new(true);
+
+    std::thread::scope(|scope| {
+        scope.spawn(|| {
+            let mut i = 0;
+            while writing.load(Ordering::Relaxed) {
+                signal.update(|values| values[0] = i);
+                i += 1;
+            }
+        });
+
+        c.bench_function("signal_concurrent_read", |b| {
+            b.iter(|| {
+                std::thread::scope(|readers| {
+                    for _ in 0..4 {
+                        readers.spawn(|| {
+                            for _ in 0..100_000 {
+                                black_box(signal.with(|values| values[0]));
+                            }
+                        });
+                    }
+                });
+            });
+        });
+
+        writing.store(false, Ordering::Relaxed);
+    });
+}
+
 fn memo_computation_benchmark(c: &mut Criterion) {
     let a: Signal<i32> = Signal::new(5);
     let b: Signal<i32> = Signal::new(10);
@@ -51,8 +84,87 @@ fn memo_computation_benchmark(c: &mut Criterion) {
     });
 }
 
+fn signal_many_maps_benchmark(c: &mut Criterion) {
+    let mut group = c.benchmark_group("signal_many_maps");
+
+    for map_count in [1, 10, 100].iter() {
+        // Shared fan-out: one watcher on the source drives every map
+        let source: Signal<i32> = Signal::new(0);
+        let maps: Vec<_> = (0..*map_count).map(|_| source.map(|n| n * 2)).collect();
+
+        group.bench_with_input(BenchmarkId::new("fan_out", map_count), map_count, |b, _| {
+            let mut i = 0;
+            b.iter(|| {
+                source.set(black_box(i));
+                i += 1;
+            });
+        });
+        drop(maps);
+
+        // Baseline: one watcher per derived signal
+        let source: Signal<i32> = Signal::new(0);
+        let watchers: Vec<_> = (0..*map_count)
+            .map(|_| {
+                let derived = Signal::new(0);
+                source.watch(move |n| derived.set(n * 2))
+            })
+            .collect();
+
+        group.bench_with_input(
+            BenchmarkId::new("watcher_per_map", map_count),
+            map_count,
+            |b, _| {
+                let mut i = 0;
+                b.iter(|| {
+                    source.set(black_box(i));
+                    i += 1;
+                });
+            },
+        );
+        drop(watchers);
+    }
+    group.finish();
+}
+
+fn signal_map_chain_benchmark(c: &mut Criterion) {
+    let mut group = c.benchmark_group("signal_map_chain");
+
+    // One node and one effect per step
+    let source: Signal<i32> = Signal::new(0);
+    let chained = source
+        .map_tracked(|n| n + 1)
+        .map_tracked(|n| n * 2)
+        .map_tracked(|n| n - 3);
+    group.bench_function("chained", |b| {
+        let mut i = 0;
+        b.iter(|| {
+            source.set(black_box(i));
+            i += 1;
+        });
+    });
+    drop(chained);
+
+    // The same steps fused into a single effect
+    let source: Signal<i32> = Signal::new(0);
+    let fused = source
+        .map_fused(|n| n + 1)
+        .then(|n| n * 2)
+        .then(|n| n - 3)
+        .build();
+    group.bench_function("fused", |b| {
+        let mut i = 0;
+        b.iter(|| {
+            source.set(black_box(i));
+            i += 1;
+        });
+    });
+    drop(fused);
+    group.finish();
+}
+
 fn store_update_benchmark(c: &mut Criterion) {
     #[derive(Clone)]
+    #[allow(dead_code)]
     struct State {
         counter: usize,
         name: String,
@@ -74,6 +186,19 @@ fn store_update_benchmark(c: &mut Criterion) {
     });
 }
 
+fn store_get_cow_benchmark(c: &mut Criterion) {
+    let store = Store::new(vec![0u64; 10_000]);
+
+    let mut group = c.benchmark_group("store_large_read");
+    group.bench_function("get", |b| {
+        b.iter(|| black_box(store.get().len()));
+    });
+    group.bench_function("get_cow", |b| {
+        b.iter(|| black_box(store.get_cow().len()));
+    });
+    group.finish();
+}
+
 fn store_subscribe_benchmark(c: &mut Criterion) {
     #[derive(Clone)]
     struct State {
@@ -111,8 +236,12 @@ criterion_group!(
     signal_creation_benchmark,
     signal_read_benchmark,
     signal_write_benchmark,
+    signal_concurrent_read_benchmark,
     memo_computation_benchmark,
+    signal_many_maps_benchmark,
+    signal_map_chain_benchmark,
     store_update_benchmark,
+    store_get_cow_benchmark,
     store_subscribe_benchmark,
 );
 criterion_main!(benches);
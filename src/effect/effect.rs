@@ -1,26 +1,133 @@
-use crate::runtime::{ReactiveRuntime, RuntimeInner};
-use std::sync::{Arc, RwLock, Weak};
+use crate::runtime::{self, ReactiveRuntime, ReadFilter, ThreadPool};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, Weak};
 
 /// A side effect that runs when its dependencies change.
 pub struct Effect {
     id: usize,
-    runtime: Weak<RwLock<RuntimeInner>>,
+    runtime: Weak<ReactiveRuntime>,
+    // Decides which reads are tracked, for effects from `new_filtered`
+    _filter: Option<ReadFilter>,
 }
 
 impl Effect {
     /// Create a new effect that runs when dependencies change.
     pub fn new<F>(effect: F) -> Self
+    where
+        F: Fn() + Send + Sync + 'static,
+    {
+        Self::create(effect, None)
+    }
+
+    /// Create an effect that only tracks the reads `track` allows.
+    ///
+    /// `track` receives the id of each signal, memo or store the effect reads.
+    /// Reads it rejects are untracked, so changes to them do not re-run the
+    /// effect.
+    pub fn new_filtered<P, F>(track: P, effect: F) -> Self
+    where
+        P: Fn(usize) -> bool + Send + Sync + 'static,
+        F: Fn() + Send + Sync + 'static,
+    {
+        Self::create(effect, Some(Arc::new(track)))
+    }
+
+    /// Create an effect whose body returns a cleanup for what it set up.
+    ///
+    /// The cleanup from each run is called right before the next run and
+    /// once more when the effect is dropped, like returning a teardown
+    /// function from React's `useEffect`.
+    pub fn with_cleanup<F, C>(effect: F) -> Self
+    where
+        F: Fn() -> C + Send + Sync + 'static,
+        C: FnOnce() + Send + 'static,
+    {
+        let runtime = ReactiveRuntime::current();
+        Self::create(move || runtime.on_cleanup(effect()), None)
+    }
+
+    /// Create an effect that runs on a shared background thread pool.
+    ///
+    /// Notifications queue a run instead of running the effect inline, so
+    /// writers are not stalled by blocking work. Runs of one effect never
+    /// overlap: changes arriving during a run schedule exactly one more run,
+    /// which sees the latest values. The first run is queued as well.
+    pub fn new_background<F>(effect: F) -> Self
     where
         F: Fn() + Send + Sync + 'static,
     {
         let runtime = ReactiveRuntime::current();
         let id = runtime.next_id();
         let effect = Arc::new(effect);
+        let state = Arc::new(Mutex::new(BackgroundState::default()));
+
+        let schedule = {
+            let runtime = Arc::clone(&runtime);
+            move || {
+                {
+                    let mut state = state.lock().unwrap();
+                    if state.running {
+                        state.pending = true;
+                        return;
+                    }
+                    state.running = true;
+                }
+                runtime.begin_work();
+                let job = BackgroundJob {
+                    runtime: Arc::clone(&runtime),
+                    state: Arc::clone(&state),
+                };
+                let effect = Arc::clone(&effect);
+                ThreadPool::global().execute(move || loop {
+                    let runtime = &job.runtime;
+                    // Skip queued runs of an effect that has since been dropped
+                    if runtime.has_observer(id) {
+                        runtime.run_cleanups(id);
+                        runtime.with_runtime(|| runtime.retrack(id, || effect()));
+                    }
+                    let mut state = job.state.lock().unwrap();
+                    if !std::mem::take(&mut state.pending) {
+                        state.running = false;
+                        break;
+                    }
+                });
+            }
+        };
+        let schedule = Arc::new(schedule);
+        runtime.create_observer(id, {
+            let schedule = Arc::clone(&schedule);
+            move || schedule()
+        });
+        schedule();
+
+        Self {
+            id,
+            runtime: Arc::downgrade(&runtime),
+            _filter: None,
+        }
+    }
+
+    fn create<F>(effect: F, filter: Option<ReadFilter>) -> Self
+    where
+        F: Fn() + Send + Sync + 'static,
+    {
+        let runtime = ReactiveRuntime::current();
+        let id = runtime.next_id();
+        if let Some(filter) = &filter {
+            runtime.set_read_filter(id, filter);
+        }
+        let effect = Arc::new(effect);
         let effect_clone = Arc::clone(&effect);
 
-        // Register the effect with the runtime
-        runtime.create_observer(id, move || {
-            effect_clone();
+        // Register the effect with the runtime, re-tracking reads on every run
+        runtime.create_observer(id, {
+            let runtime = Arc::clone(&runtime);
+            move || {
+                runtime.run_cleanups(id);
+                // Rerun in this runtime even if the writer is outside its scope,
+                // so `on_cleanup` and primitives created by the effect land here
+                runtime.with_runtime(|| runtime.retrack(id, || effect_clone()));
+            }
         });
 
         // Run immediately within the observer context to track dependencies
@@ -30,17 +137,113 @@ impl Effect {
 
         Self {
             id,
-            runtime: Arc::downgrade(&runtime.inner()),
+            runtime: Arc::downgrade(&runtime),
+            _filter: filter,
+        }
+    }
+
+    /// Get the effect's unique ID.
+    pub fn id(&self) -> usize {
+        self.id
+    }
+
+    /// Stop the effect from running until it is resumed.
+    ///
+    /// Dependency changes while paused are remembered, not queued.
+    pub fn pause(&self) {
+        if let Some(runtime) = self.runtime.upgrade() {
+            runtime.pause_observer(self.id);
+        }
+    }
+
+    /// Resume the effect, running it once if a dependency changed while paused.
+    pub fn resume(&self) {
+        if let Some(runtime) = self.runtime.upgrade() {
+            runtime.resume_observer(self.id);
+        }
+    }
+
+    /// Check whether the effect is paused.
+    pub fn is_paused(&self) -> bool {
+        self.runtime
+            .upgrade()
+            .is_some_and(|runtime| runtime.is_observer_paused(self.id))
+    }
+}
+
+/// Register a cleanup for the effect that is currently running.
+///
+/// Call this from inside an effect's closure to tear down what the run set
+/// up, such as a subscription or a timer. The cleanup runs before the
+/// effect's next run and when the effect is dropped; registering several
+/// runs them newest first. Outside of any effect the cleanup is dropped
+/// without running.
+pub fn on_cleanup(cleanup: impl FnOnce() + Send + 'static) {
+    ReactiveRuntime::current().on_cleanup(cleanup);
+}
+
+/// Create an effect with explicit dependencies.
+///
+/// Only reads made by `deps` are tracked. Its result is passed to `effect`,
+/// whose own reads are untracked, so a branch inside `effect` can never add
+/// or drop dependencies between runs. Runs once immediately, like
+/// [`Effect::new`]; see [`on_deferred`] to skip that first run.
+pub fn on<D, Deps, F>(deps: Deps, effect: F) -> Effect
+where
+    Deps: Fn() -> D + Send + Sync + 'static,
+    F: Fn(D) + Send + Sync + 'static,
+{
+    Effect::new(move || {
+        let value = deps();
+        runtime::untrack(|| effect(value));
+    })
+}
+
+/// Like [`on`], but `effect` only runs when the dependencies change, not
+/// immediately.
+pub fn on_deferred<D, Deps, F>(deps: Deps, effect: F) -> Effect
+where
+    Deps: Fn() -> D + Send + Sync + 'static,
+    F: Fn(D) + Send + Sync + 'static,
+{
+    let first = AtomicBool::new(true);
+    on(deps, move |value| {
+        if !first.swap(false, Ordering::SeqCst) {
+            effect(value);
         }
+    })
+}
+
+/// Run state of a background effect.
+#[derive(Default)]
+struct BackgroundState {
+    running: bool,
+    // A change arrived while running, so run once more
+    pending: bool,
+}
+
+/// A queued background run, reporting completion to its runtime when dropped.
+struct BackgroundJob {
+    runtime: Arc<ReactiveRuntime>,
+    state: Arc<Mutex<BackgroundState>>,
+}
+
+impl Drop for BackgroundJob {
+    fn drop(&mut self) {
+        // A panicking run must not leave the effect stuck as running
+        if std::thread::panicking() {
+            let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+            state.running = false;
+            state.pending = false;
+        }
+        self.runtime.end_work();
     }
 }
 
 impl Drop for Effect {
     fn drop(&mut self) {
         if let Some(runtime) = self.runtime.upgrade() {
-            if let Ok(mut runtime) = runtime.write() {
-                runtime.remove_observer(self.id);
-            }
+            runtime.remove_observer(self.id);
         }
     }
 }
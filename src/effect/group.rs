@@ -0,0 +1,47 @@
+use super::Effect;
+use std::sync::Mutex;
+
+/// A set of effects that are disposed together.
+///
+/// Effects created through [`effect`](Self::effect) live as long as the
+/// group; dropping the group drops every member, removing their observers.
+#[derive(Default)]
+pub struct EffectGroup {
+    effects: Mutex<Vec<Effect>>,
+}
+
+impl EffectGroup {
+    /// Create an empty group.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create an effect owned by this group, returning its ID.
+    pub fn effect<F>(&self, f: F) -> usize
+    where
+        F: Fn() + Send + Sync + 'static,
+    {
+        let effect = Effect::new(f);
+        let id = effect.id();
+        self.effects.lock().unwrap().push(effect);
+        id
+    }
+
+    /// Get the number of effects in the group.
+    pub fn len(&self) -> usize {
+        self.effects.lock().unwrap().len()
+    }
+
+    /// Check whether the group has no effects.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl Drop for EffectGroup {
+    fn drop(&mut self) {
+        // Drop the members outside the lock, their teardown may reenter
+        let effects = std::mem::take(self.effects.get_mut().unwrap());
+        drop(effects);
+    }
+}
@@ -1,3 +1,6 @@
+#[allow(clippy::module_inception)]
 mod effect;
+mod group;
 
-pub use effect::Effect;
+pub use effect::{on, on_cleanup, on_deferred, Effect};
+pub use group::EffectGroup;
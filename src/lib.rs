@@ -1,11 +1,15 @@
 pub mod effect;
 pub mod memo;
+pub mod resource;
 pub mod runtime;
 pub mod signal;
 
-pub use effect::Effect;
+pub use effect::{on, on_cleanup, on_deferred, Effect, EffectGroup};
 pub use memo::Memo;
-pub use signal::{Signal, WatchGuard};
+pub use runtime::{batch, Owner};
+pub use signal::{
+    bind, create_signal, DynSignal, ReadSignal, Signal, VecSignalExt, WatchGuard, WriteSignal,
+};
 
 pub mod store;
-pub use store::Store;
+pub use store::{computed, Store};
@@ -1,12 +1,24 @@
-use crate::runtime::ReactiveRuntime;
-use std::sync::{Arc, RwLock};
+use crate::runtime::{MemoHook, ReactiveRuntime};
+use crate::{Effect, Signal, Store};
+use std::sync::{Arc, Mutex, RwLock};
+use std::thread;
+use std::time::Duration;
 
 /// A memoized computed value that automatically tracks dependencies.
+///
+/// A write first marks every memo downstream of it dirty, then recomputes
+/// deduplicating memos, then runs the affected effects. Plain memos
+/// recompute lazily on their next read, so an effect reading a memo during
+/// a write always sees the settled value, never one computed from a
+/// half-invalidated graph.
 #[derive(Clone)]
 pub struct Memo<T> {
     cached_value: Arc<RwLock<Option<T>>>,
     compute: Arc<dyn Fn() -> T + Send + Sync>,
     id: usize,
+    runtime: Arc<ReactiveRuntime>,
+    // Eager recompute hook for deduplicating memos, owned here
+    _dedupe: Option<MemoHook>,
 }
 
 impl<T: Clone + 'static> Memo<T> {
@@ -25,12 +37,105 @@ impl<T: Clone + 'static> Memo<T> {
             cached_value: Arc::new(RwLock::new(None)),
             compute: Arc::new(compute),
             id,
+            runtime,
+            _dedupe: None,
         }
     }
 
+    /// Create a memo that stops propagation when it recomputes to an equal value.
+    ///
+    /// When a dependency changes and something depends on this memo, it
+    /// recomputes right away. Dependent memos and effects are only dirtied if
+    /// the new value differs from the cached one, so the memo acts as a
+    /// deduplication barrier for the rest of the graph.
+    pub fn new_deduped<F>(compute: F) -> Self
+    where
+        T: PartialEq + Send + Sync,
+        F: Fn() -> T + Send + Sync + 'static,
+    {
+        Self::new_with_eq(compute, T::eq)
+    }
+
+    /// Like [`new_deduped`](Self::new_deduped), comparing values with `eq`.
+    ///
+    /// Use this for types without `PartialEq`, or to treat values as equal
+    /// when they differ in ways dependents do not care about.
+    pub fn new_with_eq<F, E>(compute: F, eq: E) -> Self
+    where
+        T: Send + Sync,
+        F: Fn() -> T + Send + Sync + 'static,
+        E: Fn(&T, &T) -> bool + Send + Sync + 'static,
+    {
+        let mut memo = Self::new(compute);
+        let hook: MemoHook = Arc::new({
+            let cached_value = Arc::clone(&memo.cached_value);
+            let compute = Arc::clone(&memo.compute);
+            let runtime = Arc::clone(&memo.runtime);
+            let id = memo.id;
+            move || {
+                let value = runtime.with_observer(id, || compute());
+                runtime.mark_memo_clean(id);
+                let mut cached = cached_value.write().unwrap();
+                let changed = cached.as_ref().is_none_or(|cached| !eq(cached, &value));
+                *cached = Some(value);
+                changed
+            }
+        });
+        memo.runtime.set_memo_hook(memo.id, &hook);
+        memo._dedupe = Some(hook);
+        memo
+    }
+
+    /// Create a memo that is also invalidated every `interval`.
+    ///
+    /// For values that depend on time as well as on tracked reads, like
+    /// whether a token has expired. A background timer marks the memo dirty
+    /// on every tick, so the next read recomputes even if no dependency
+    /// changed. The timer stops at the first tick after every handle to the
+    /// memo is dropped.
+    pub fn new_with_refresh<F>(interval: Duration, compute: F) -> Self
+    where
+        T: Send + Sync,
+        F: Fn() -> T + Send + Sync + 'static,
+    {
+        let ticks = Signal::new(0u64);
+        let memo = Self::new({
+            let ticks = ticks.clone();
+            move || {
+                ticks.with(|_| ());
+                compute()
+            }
+        });
+        let alive = Arc::downgrade(&memo.cached_value);
+        thread::Builder::new()
+            .name("tincan-refresh".into())
+            .spawn(move || loop {
+                thread::sleep(interval);
+                if alive.strong_count() == 0 {
+                    break;
+                }
+                ticks.update(|n| *n = n.wrapping_add(1));
+            })
+            .expect("failed to spawn tincan refresh thread");
+        memo
+    }
+
+    /// Create a new memo whose computation keeps mutable state between runs.
+    ///
+    /// The state lives behind a `Mutex` for the lifetime of the memo, while the
+    /// computed value is still recached on every dirty cycle.
+    pub fn new_stateful<S, F>(init_state: S, compute: F) -> Self
+    where
+        S: Send + 'static,
+        F: Fn(&mut S) -> T + Send + Sync + 'static,
+    {
+        let state = Mutex::new(init_state);
+        Self::new(move || compute(&mut state.lock().unwrap()))
+    }
+
     /// Get the current value, recomputing if necessary.
     pub fn get(&self) -> T {
-        let runtime = ReactiveRuntime::current();
+        let runtime = &self.runtime;
 
         // Track this read in the reactive context
         runtime.track_read(self.id);
@@ -48,9 +153,27 @@ impl<T: Clone + 'static> Memo<T> {
         }
     }
 
+    /// Mirror this memo's value into `store` until the returned effect is dropped.
+    ///
+    /// The store is set right away and again whenever a dependency changes,
+    /// so the memo is evaluated eagerly instead of on the next `get`.
+    pub fn sync_to_store(&self, store: &Store<T>) -> Effect
+    where
+        T: Send + Sync,
+    {
+        let memo = self.clone();
+        let store = store.clone();
+        Effect::new(move || {
+            store.set(memo.get());
+        })
+    }
+
     /// Read the memoized value with a function without cloning.
+    ///
+    /// The cached value stays locked while `f` runs, so `f` must not write to
+    /// anything this memo depends on.
     pub fn with<R>(&self, f: impl FnOnce(&T) -> R) -> R {
-        let runtime = ReactiveRuntime::current();
+        let runtime = &self.runtime;
         runtime.track_read(self.id);
 
         if runtime.is_memo_dirty(self.id) {
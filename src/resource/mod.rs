@@ -0,0 +1,4 @@
+#[allow(clippy::module_inception)]
+mod resource;
+
+pub use resource::{BlockingSpawner, BoxFuture, Resource, Spawn};
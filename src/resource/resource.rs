@@ -0,0 +1,166 @@
+use crate::effect::{self, Effect};
+use crate::runtime::{ReactiveRuntime, ThreadPool};
+use crate::signal::Signal;
+use std::future::Future;
+use std::pin::{pin, Pin};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::task::{Context, Poll, Wake, Waker};
+use std::thread::{self, Thread};
+
+/// A boxed task handed to a [`Spawn`] implementation.
+pub type BoxFuture = Pin<Box<dyn Future<Output = ()> + Send + 'static>>;
+
+/// Runs the fetch tasks of a [`Resource`] on some executor.
+///
+/// Implemented for closures, so a tokio user can pass
+/// `|task| { tokio::spawn(task); }`.
+pub trait Spawn: Send + Sync + 'static {
+    /// Start running `task` to completion.
+    fn spawn(&self, task: BoxFuture);
+}
+
+impl<F> Spawn for F
+where
+    F: Fn(BoxFuture) + Send + Sync + 'static,
+{
+    fn spawn(&self, task: BoxFuture) {
+        self(task)
+    }
+}
+
+/// Runs each task by blocking a worker of the shared background pool.
+///
+/// Needs no async runtime, but a worker is occupied until its task finishes,
+/// so prefer your executor's spawner for I/O-heavy fetchers.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct BlockingSpawner;
+
+impl Spawn for BlockingSpawner {
+    fn spawn(&self, task: BoxFuture) {
+        ThreadPool::global().execute(move || block_on(task));
+    }
+}
+
+/// Wakes a thread parked in `block_on`.
+struct ThreadWaker(Thread);
+
+impl Wake for ThreadWaker {
+    fn wake(self: Arc<Self>) {
+        self.0.unpark();
+    }
+}
+
+fn block_on<F: Future>(future: F) -> F::Output {
+    let mut future = pin!(future);
+    let waker = Waker::from(Arc::new(ThreadWaker(thread::current())));
+    let mut cx = Context::from_waker(&waker);
+    loop {
+        match future.as_mut().poll(&mut cx) {
+            Poll::Ready(output) => return output,
+            Poll::Pending => thread::park(),
+        }
+    }
+}
+
+/// Reports a fetch as finished to its runtime, even if it is dropped unfinished.
+struct FetchWork(Arc<ReactiveRuntime>);
+
+impl Drop for FetchWork {
+    fn drop(&mut self) {
+        self.0.end_work();
+    }
+}
+
+/// Asynchronously loaded data that refetches whenever its source changes.
+///
+/// The fetcher runs on the given [`Spawn`] implementation, once on creation
+/// and again after every change of the source signal. Results of fetches
+/// superseded by a newer source value are discarded. A failed fetch sets
+/// [`error`](Self::error) but keeps the last loaded [`value`](Self::value).
+///
+/// Fetches count as queued work of the runtime, so
+/// [`ReactiveRuntime::settle`] waits for them.
+pub struct Resource<T, E> {
+    loading: Signal<bool>,
+    value: Signal<Option<T>>,
+    error: Signal<Option<E>>,
+    _effect: Effect,
+}
+
+impl<T, E> Resource<T, E>
+where
+    T: Clone + Send + Sync + 'static,
+    E: Clone + Send + Sync + 'static,
+{
+    /// Create a resource fetching with `fetcher` for every value of `source`.
+    pub fn new<S, F, Fut>(source: &Signal<S>, fetcher: F, spawner: impl Spawn) -> Self
+    where
+        S: Clone + Send + Sync + 'static,
+        F: Fn(S) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<T, E>> + Send + 'static,
+    {
+        let runtime = ReactiveRuntime::current();
+        let loading = Signal::new(false);
+        let value = Signal::new(None);
+        let error = Signal::new(None);
+        let generation = Arc::new(AtomicU64::new(0));
+
+        let effect = effect::on(
+            {
+                let source = source.clone();
+                move || source.get()
+            },
+            {
+                let (loading, value, error) = (loading.clone(), value.clone(), error.clone());
+                move |input| {
+                    let current = generation.fetch_add(1, Ordering::SeqCst) + 1;
+                    loading.set(true);
+                    runtime.begin_work();
+                    let work = FetchWork(Arc::clone(&runtime));
+                    let fetch = fetcher(input);
+                    let generation = Arc::clone(&generation);
+                    let (loading, value, error) = (loading.clone(), value.clone(), error.clone());
+                    spawner.spawn(Box::pin(async move {
+                        let _work = work;
+                        let result = fetch.await;
+                        // A newer fetch owns the signals now
+                        if generation.load(Ordering::SeqCst) != current {
+                            return;
+                        }
+                        match result {
+                            Ok(loaded) => {
+                                value.set(Some(loaded));
+                                error.set(None);
+                            }
+                            Err(failed) => error.set(Some(failed)),
+                        }
+                        loading.set(false);
+                    }));
+                }
+            },
+        );
+
+        Self {
+            loading,
+            value,
+            error,
+            _effect: effect,
+        }
+    }
+
+    /// Get a signal that is `true` while a fetch is in flight.
+    pub fn loading(&self) -> Signal<bool> {
+        self.loading.clone()
+    }
+
+    /// Get a signal of the last successfully loaded value.
+    pub fn value(&self) -> Signal<Option<T>> {
+        self.value.clone()
+    }
+
+    /// Get a signal of the latest fetch's error, cleared by a successful fetch.
+    pub fn error(&self) -> Signal<Option<E>> {
+        self.error.clone()
+    }
+}
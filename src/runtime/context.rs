@@ -1,6 +1,61 @@
-use std::collections::{HashMap, HashSet};
+use std::any::{Any, TypeId};
+use std::cell::{Cell, RefCell};
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::panic::{self, AssertUnwindSafe};
 use std::sync::atomic::{AtomicUsize, Ordering};
-use std::sync::{Arc, Mutex, RwLock};
+use std::sync::{Arc, Condvar, Mutex, OnceLock, RwLock, Weak};
+use std::thread;
+
+use super::snapshot::{GateGuard, SnapshotGate};
+#[cfg(feature = "metrics")]
+use super::Metrics;
+use super::RuntimeStats;
+
+type Observer = Arc<dyn Fn() + Send + Sync>;
+pub(crate) type ReadFilter = Arc<dyn Fn(usize) -> bool + Send + Sync>;
+/// Eagerly recomputes a memo, returning whether its value changed.
+pub(crate) type MemoHook = Arc<dyn Fn() -> bool + Send + Sync>;
+/// Told whether a source just gained its first dependent (`true`) or lost
+/// its last one (`false`).
+pub(crate) type ObservedHook = Arc<dyn Fn(bool) + Send + Sync>;
+
+type Cleanup = Box<dyn FnOnce() + Send>;
+pub(crate) type Equality<T> = Arc<dyn Fn(&T, &T) -> bool + Send + Sync>;
+type ErrorHandler = Arc<dyn Fn(usize, Box<dyn Any + Send>) + Send + Sync>;
+
+/// An observer taken out of the graph, torn down when dropped.
+///
+/// Dropping runs its pending cleanups, newest first, then drops the closure.
+/// Callers drop it only after releasing the context lock.
+struct Removed {
+    observer: Option<Observer>,
+    cleanups: Vec<Cleanup>,
+}
+
+impl Drop for Removed {
+    fn drop(&mut self) {
+        for cleanup in self.cleanups.drain(..).rev() {
+            cleanup();
+        }
+        self.observer.take();
+    }
+}
+
+/// What to do with a memo's dependents after marking it dirty.
+enum MemoDirty {
+    Already,
+    Propagate(Vec<usize>),
+    Check(MemoHook, Vec<usize>),
+}
+
+/// Work collected while dirtying the graph for one write.
+#[derive(Default)]
+struct Wave {
+    // Effects to run, in first-reached order
+    effects: Vec<usize>,
+    // Deduplicating memos to recompute once the dirtying pass is done
+    checks: VecDeque<(usize, MemoHook, Vec<usize>)>,
+}
 
 /// Inner runtime state that can be shared.
 pub struct RuntimeInner {
@@ -8,155 +63,686 @@ pub struct RuntimeInner {
 }
 
 impl RuntimeInner {
-    pub fn remove_observer(&mut self, observer_id: usize) {
-        let mut ctx = self.context.lock().unwrap();
-        // Remove observer
-        ctx.observers.remove(&observer_id);
+    pub fn remove_observer(&self, observer_id: usize) {
+        let (removed, transitions) = {
+            let mut ctx = self.context.lock().unwrap();
+            (ctx.remove_observer(observer_id), ctx.take_transitions())
+        };
+        // Drop the observer outside the lock, its cleanups and the primitives
+        // it owns may reach back into the runtime.
+        drop(removed);
+        transitions.fire();
+    }
+}
 
-        // Clear dependencies
-        if let Some(old_deps) = ctx.observer_deps.remove(&observer_id) {
-            for signal_id in old_deps {
-                if let Some(deps) = ctx.dependencies.get_mut(&signal_id) {
-                    deps.remove(&observer_id);
-                }
-            }
+/// Observed-state changes of hooked sources, reported outside the lock.
+#[must_use]
+struct Transitions(Vec<(ObservedHook, bool)>);
+
+impl Transitions {
+    fn fire(self) {
+        for (hook, observed) in self.0 {
+            hook(observed);
         }
     }
 }
 
-/// Global reactive runtime for managing reactive primitives.
+// IDs are unique across runtimes, so a stray read can never alias an observer
+static NEXT_ID: AtomicUsize = AtomicUsize::new(0);
+
+/// Reactive runtime for managing reactive primitives.
+///
+/// Primitives belong to the runtime that was current when they were created.
+/// Code outside of any scope uses a global runtime shared by all threads.
 pub struct ReactiveRuntime {
-    next_id: AtomicUsize,
     inner: Arc<RwLock<RuntimeInner>>,
+    // Effect runs queued or running off the writer's thread
+    outstanding: Mutex<usize>,
+    settled: Condvar,
+    // Keeps writes out while `with_snapshot` reads
+    gate: SnapshotGate,
+    // Receives panics from observers instead of unwinding into the writer
+    error_handler: RwLock<Option<ErrorHandler>>,
+    // Comparators registered per value type, consulted by `Signal::set`
+    equality: RwLock<HashMap<TypeId, Arc<dyn Any + Send + Sync>>>,
 }
 
 impl ReactiveRuntime {
-    /// Get or create the current reactive runtime.
-    pub fn current() -> &'static Self {
-        use std::sync::OnceLock;
-        static RUNTIME: OnceLock<ReactiveRuntime> = OnceLock::new();
-        RUNTIME.get_or_init(|| ReactiveRuntime {
-            next_id: AtomicUsize::new(0),
+    /// Create a new runtime with its own, isolated reactive graph.
+    pub fn new() -> Arc<Self> {
+        Arc::new(ReactiveRuntime {
             inner: Arc::new(RwLock::new(RuntimeInner {
                 context: Mutex::new(ReactiveContext::new()),
             })),
+            outstanding: Mutex::new(0),
+            settled: Condvar::new(),
+            equality: RwLock::new(HashMap::new()),
+            error_handler: RwLock::new(None),
+            gate: SnapshotGate::default(),
         })
     }
 
+    /// Get the global runtime used outside of any scope.
+    pub fn global() -> Arc<Self> {
+        static RUNTIME: OnceLock<Arc<ReactiveRuntime>> = OnceLock::new();
+        Arc::clone(RUNTIME.get_or_init(ReactiveRuntime::new))
+    }
+
+    /// Get the innermost scoped runtime on this thread, or the global runtime.
+    ///
+    /// # Panics
+    ///
+    /// Panics in strict mode if no scoped runtime is active on this thread.
+    pub fn current() -> Arc<Self> {
+        if let Some(runtime) = RUNTIME_STACK.with(|stack| stack.borrow().last().cloned()) {
+            return runtime;
+        }
+        if STRICT.with(Cell::get) {
+            panic!("no scoped reactive runtime is active in strict mode");
+        }
+        Self::global()
+    }
+
+    /// Run a function with this runtime as the current runtime on this thread.
+    ///
+    /// The previous runtime is restored when `f` returns or unwinds.
+    pub fn with_runtime<R>(self: &Arc<Self>, f: impl FnOnce() -> R) -> R {
+        RUNTIME_STACK.with(|stack| stack.borrow_mut().push(Arc::clone(self)));
+        let _scope = ScopeGuard;
+        f()
+    }
+
+    /// Run a function inside a fresh, isolated runtime.
+    pub fn scope<R>(f: impl FnOnce() -> R) -> R {
+        Self::new().with_runtime(f)
+    }
+
+    /// Spawn a thread that runs `f` in the current runtime.
+    ///
+    /// The runtime stack is per thread, so a plain `std::thread::spawn`
+    /// inside a [`scope`](Self::scope) would fall back to the global runtime.
+    /// Only the runtime carries over, not the running observer: reads on the
+    /// new thread are never tracked by the spawning effect.
+    pub fn spawn_scoped<F, R>(f: F) -> thread::JoinHandle<R>
+    where
+        F: FnOnce() -> R + Send + 'static,
+        R: Send + 'static,
+    {
+        thread::spawn(run_in_current_runtime(f))
+    }
+
+    /// Get the number of scoped runtimes active on this thread.
+    ///
+    /// Zero means [`current`](Self::current) falls back to the global runtime.
+    pub fn scope_depth() -> usize {
+        RUNTIME_STACK.with(|stack| stack.borrow().len())
+    }
+
+    /// Require primitives created on this thread to be inside a scope.
+    ///
+    /// In strict mode [`current`](Self::current) panics instead of silently
+    /// falling back to the global runtime. Strict mode is off by default.
+    pub fn set_strict(strict: bool) {
+        STRICT.with(|flag| flag.set(strict));
+    }
+
+    /// Block until all queued effect work for this runtime has completed.
+    ///
+    /// Background effects run on worker threads, so a write returns before
+    /// they do. Call this, typically in tests, to wait for them instead of
+    /// sleeping. Work queued while waiting is waited for as well.
+    ///
+    /// Calling this from inside a background effect deadlocks.
+    #[doc(alias = "drain_effects")]
+    pub fn settle(&self) {
+        let outstanding = self.outstanding.lock().unwrap();
+        drop(
+            self.settled
+                .wait_while(outstanding, |outstanding| *outstanding > 0)
+                .unwrap(),
+        );
+    }
+
+    /// Report observer panics to `handler` instead of unwinding into the writer.
+    ///
+    /// Without a handler, a panicking watcher or effect unwinds out of the
+    /// `set` that triggered it, and observers after it in the same wave do not
+    /// run. With one, each panic is caught, `handler` receives the observer ID
+    /// and the panic payload, and the remaining observers still run.
+    pub fn set_observer_error_handler<F>(&self, handler: F)
+    where
+        F: Fn(usize, Box<dyn Any + Send>) + Send + Sync + 'static,
+    {
+        *self.error_handler.write().unwrap() = Some(Arc::new(handler));
+    }
+
+    /// Register how to compare values of type `T` for signals in this runtime.
+    ///
+    /// `Signal::set` on a `Signal<T>` then skips the write, and notifies no
+    /// one, when `eq` says the new value equals the current one. This enables
+    /// deduplication for types that do not implement `PartialEq`. Registering
+    /// again for the same type replaces the comparator.
+    pub fn register_equality<T, F>(&self, eq: F)
+    where
+        T: 'static,
+        F: Fn(&T, &T) -> bool + Send + Sync + 'static,
+    {
+        let eq: Equality<T> = Arc::new(eq);
+        self.equality
+            .write()
+            .unwrap()
+            .insert(TypeId::of::<T>(), Arc::new(eq));
+    }
+
+    /// Get the comparator registered for `T`, if any.
+    pub(crate) fn equality<T: 'static>(&self) -> Option<Equality<T>> {
+        let equality = self.equality.read().unwrap();
+        let eq = equality.get(&TypeId::of::<T>())?;
+        eq.downcast_ref::<Equality<T>>().cloned()
+    }
+
+    /// Record that effect work has been queued off the current thread.
+    pub(crate) fn begin_work(&self) {
+        *self.outstanding.lock().unwrap() += 1;
+    }
+
+    /// Record that queued effect work has finished.
+    pub(crate) fn end_work(&self) {
+        let mut outstanding = self.outstanding.lock().unwrap();
+        *outstanding -= 1;
+        if *outstanding == 0 {
+            self.settled.notify_all();
+        }
+    }
+
     /// Get a reference to the inner runtime state.
     pub fn inner(&self) -> Arc<RwLock<RuntimeInner>> {
         Arc::clone(&self.inner)
     }
 
+    /// Run a function with exclusive access to the reactive context.
+    fn with_context<R>(&self, f: impl FnOnce(&mut ReactiveContext) -> R) -> R {
+        let (result, transitions) = {
+            let inner = self.inner.read().unwrap();
+            let mut ctx = inner.context.lock().unwrap();
+            let result = f(&mut ctx);
+            (result, ctx.take_transitions())
+        };
+        transitions.fire();
+        result
+    }
+
     /// Generate the next unique ID for a reactive primitive.
     pub fn next_id(&self) -> usize {
-        self.next_id.fetch_add(1, Ordering::SeqCst)
+        let id = NEXT_ID.fetch_add(1, Ordering::SeqCst);
+        CREATED.with(|created| {
+            if let Some(ids) = created.borrow_mut().last_mut() {
+                ids.push(id);
+            }
+        });
+        id
+    }
+
+    /// Run a function and collect the IDs of every primitive created inside it.
+    ///
+    /// Nested calls report their IDs to every enclosing call as well, so the
+    /// outermost caller always sees the full set.
+    pub fn track_created<F>(&self, f: F) -> Vec<usize>
+    where
+        F: FnOnce(),
+    {
+        let scope = CreatedScope::enter();
+        f();
+        scope.exit()
+    }
+
+    /// Run a function and report the IDs of every signal it read, sorted.
+    ///
+    /// The reads are tracked against a temporary observer that is removed
+    /// afterwards, so the function does not become a dependent of anything.
+    pub fn capture_dependencies<F, R>(&self, f: F) -> (R, Vec<usize>)
+    where
+        F: FnOnce() -> R,
+    {
+        let observer_id = NEXT_ID.fetch_add(1, Ordering::SeqCst);
+        let result = self.with_observer(observer_id, f);
+        let mut ids: Vec<usize> = self.with_context(|ctx| {
+            let ids = ctx
+                .observer_deps
+                .get(&observer_id)
+                .map(|deps| deps.iter().copied().collect())
+                .unwrap_or_default();
+            ctx.clear_dependencies(observer_id);
+            ids
+        });
+        ids.sort_unstable();
+        (result, ids)
+    }
+
+    /// Dispose the given primitives, removing them from the reactive graph.
+    ///
+    /// Each node is removed along with its edges, name and hooks, and
+    /// pending cleanups of disposed observers run, so they stop running.
+    /// Handles are not invalidated: writing a disposed signal still changes
+    /// its value, and an observer that reads it afterwards depends on it again.
+    pub fn dispose_ids(&self, ids: &[usize]) {
+        let removed: Vec<_> =
+            self.with_context(|ctx| ids.iter().map(|&id| ctx.dispose(id)).collect::<Vec<_>>());
+        drop(removed);
+    }
+
+    /// Count the observers, memos and tracked sources in this runtime's graph.
+    pub fn stats(&self) -> RuntimeStats {
+        self.with_context(|ctx| RuntimeStats {
+            observers: ctx.observers.len(),
+            memos: ctx.memo_dirty.len(),
+            tracked_sources: ctx
+                .dependencies
+                .values()
+                .filter(|dependents| !dependents.is_empty())
+                .count(),
+        })
+    }
+
+    /// Get the name a primitive was registered under, if any.
+    pub fn name_of(&self, id: usize) -> Option<String> {
+        self.with_context(|ctx| ctx.names.get(&id).cloned())
+    }
+
+    /// Get the IDs of the sources an observer or memo read on its last run.
+    ///
+    /// Sorted by ID, and empty for unknown IDs.
+    pub fn dependencies_of(&self, observer_id: usize) -> Vec<usize> {
+        let mut ids: Vec<usize> = self.with_context(|ctx| {
+            ctx.observer_deps
+                .get(&observer_id)
+                .map(|deps| deps.iter().copied().collect())
+                .unwrap_or_default()
+        });
+        ids.sort_unstable();
+        ids
+    }
+
+    /// Get the IDs of the observers and memos that depend on a source.
+    ///
+    /// Sorted by ID, and empty for unknown IDs.
+    pub fn observers_of(&self, source_id: usize) -> Vec<usize> {
+        let mut ids = self.with_context(|ctx| ctx.dependents(source_id));
+        ids.sort_unstable();
+        ids
+    }
+
+    /// Describe the reactive graph, one line per node, ordered by ID.
+    ///
+    /// Each line reads `<id> <kind> ["name"] -> [<dependents>]`, where kind is
+    /// `memo`, `observer` or `source`. Sources are listed once they are named
+    /// or have dependents. Intended for debugging; the format may change.
+    pub fn dump_graph(&self) -> String {
+        self.with_context(|ctx| {
+            let mut ids: Vec<usize> = ctx
+                .names
+                .keys()
+                .chain(ctx.dependencies.keys())
+                .chain(ctx.observers.keys())
+                .chain(ctx.memo_dirty.keys())
+                .copied()
+                .collect();
+            ids.sort_unstable();
+            ids.dedup();
+
+            let mut dump = String::new();
+            for id in ids {
+                let kind = if ctx.memo_dirty.contains_key(&id) {
+                    "memo"
+                } else if ctx.observers.contains_key(&id) {
+                    "observer"
+                } else {
+                    "source"
+                };
+                let mut dependents = ctx.dependents(id);
+                dependents.sort_unstable();
+                let name = ctx
+                    .names
+                    .get(&id)
+                    .map(|name| format!(" {name:?}"))
+                    .unwrap_or_default();
+                dump.push_str(&format!("{id} {kind}{name} -> {dependents:?}\n"));
+            }
+            dump
+        })
+    }
+
+    /// Register a display name for a primitive.
+    pub(crate) fn set_name(&self, id: usize, name: String) {
+        self.with_context(|ctx| {
+            ctx.names.insert(id, name);
+        });
+    }
+
+    /// Start collecting write and run counts, discarding any previous window.
+    #[cfg(feature = "metrics")]
+    pub fn enable_metrics(&self) {
+        self.with_context(|ctx| ctx.metrics = Some(Metrics::default()));
+    }
+
+    /// Stop collecting metrics.
+    #[cfg(feature = "metrics")]
+    pub fn disable_metrics(&self) {
+        self.with_context(|ctx| ctx.metrics = None);
+    }
+
+    /// Get the metrics collected since they were last enabled.
+    ///
+    /// Returns an empty report when metrics are disabled.
+    #[cfg(feature = "metrics")]
+    pub fn metrics(&self) -> Metrics {
+        self.with_context(|ctx| ctx.metrics.clone().unwrap_or_default())
     }
 
     /// Track a read of a signal by the current observer.
     pub fn track_read(&self, signal_id: usize) {
-        CONTEXT.with(|ctx| {
-            let mut ctx = ctx.lock().unwrap();
-            if let Some(current_observer) = ctx.current_observer {
-                // Add dependency: signal -> observer
-                ctx.dependencies
-                    .entry(signal_id)
-                    .or_insert_with(HashSet::new)
-                    .insert(current_observer);
-                // Track that this observer depends on this signal
-                ctx.observer_deps
-                    .entry(current_observer)
-                    .or_insert_with(HashSet::new)
-                    .insert(signal_id);
+        if let Some(current_observer) = CURRENT_OBSERVER.with(Cell::get) {
+            let filter = self.with_context(|ctx| match ctx.read_filters.get(&current_observer) {
+                Some(filter) => Some(filter.clone()),
+                None => {
+                    ctx.add_dependency(signal_id, current_observer);
+                    None
+                }
+            });
+            // Ask the filter outside the lock, then record the read if allowed
+            if filter
+                .and_then(|filter| filter.upgrade())
+                .is_some_and(|track| track(signal_id))
+            {
+                self.with_context(|ctx| ctx.add_dependency(signal_id, current_observer));
             }
+        }
+    }
+
+    /// Restrict which reads establish dependencies for an observer.
+    ///
+    /// The caller owns the filter, so the graph never drops user closures.
+    pub(crate) fn set_read_filter(&self, observer_id: usize, filter: &ReadFilter) {
+        self.with_context(|ctx| {
+            ctx.read_filters.insert(observer_id, Arc::downgrade(filter));
         });
     }
 
     /// Notify all observers that depend on a signal.
     pub fn notify_observers(&self, signal_id: usize) {
-        CONTEXT.with(|ctx| {
-            let ctx_ref = ctx.lock().unwrap();
-            if let Some(observers) = ctx_ref.dependencies.get(&signal_id) {
-                // Collect observers to avoid borrow issues
-                let observers: Vec<usize> = observers.iter().copied().collect();
-                drop(ctx_ref);
-
-                for observer_id in observers {
-                    self.mark_observer_dirty(observer_id);
-                }
+        // Collect observers so no lock is held while they run
+        let observers = self.with_context(|ctx| {
+            #[cfg(feature = "metrics")]
+            if let Some(metrics) = ctx.metrics.as_mut() {
+                metrics.record_write(signal_id);
             }
+            ctx.dependents(signal_id)
         });
+        // Dirty every memo on every path first, then recompute deduplicating
+        // memos, then run each affected effect once. No memo or effect ever
+        // computes from a memo on a path not yet invalidated.
+        let mut wave = Wave::default();
+        for observer_id in observers {
+            self.mark_observer_dirty(observer_id, &mut wave);
+        }
+        while let Some((memo_id, hook, dependents)) = wave.checks.pop_front() {
+            // A read earlier in this loop may have recomputed it already,
+            // leaving nothing to compare against, so propagate to be safe
+            let dirty = self.is_memo_dirty(memo_id);
+            if dirty && !hook() {
+                continue;
+            }
+            for dependent_id in dependents {
+                self.mark_observer_dirty(dependent_id, &mut wave);
+            }
+        }
+        for observer_id in wave.effects {
+            self.schedule_effect(observer_id);
+        }
     }
 
     /// Mark an observer (memo or effect) as dirty and propagate to dependents.
-    fn mark_observer_dirty(&self, observer_id: usize) {
-        let effects_to_run = CONTEXT.with(|ctx| {
-            let ctx_ref = ctx.lock().unwrap();
-            let mut effects_to_run = Vec::new();
-
-            // If it's a memo, mark it as dirty
-            if ctx_ref.memo_dirty.contains_key(&observer_id) {
-                let already_dirty = ctx_ref
-                    .memo_dirty
-                    .get(&observer_id)
-                    .copied()
-                    .unwrap_or(false);
-                if !already_dirty {
-                    drop(ctx_ref);
-                    CONTEXT.with(|ctx| {
-                        ctx.lock().unwrap().memo_dirty.insert(observer_id, true);
-                    });
-
-                    // Recursively mark dependents as dirty
-                    let dependents = CONTEXT.with(|ctx| {
-                        ctx.lock()
-                            .unwrap()
-                            .dependencies
-                            .get(&observer_id)
-                            .map(|deps| deps.iter().copied().collect::<Vec<_>>())
-                    });
-                    if let Some(dependents) = dependents {
-                        for dependent_id in dependents {
-                            self.mark_observer_dirty(dependent_id);
-                        }
-                        return effects_to_run;
-                    }
+    ///
+    /// Effects reached are collected into the wave, in first-reached order.
+    /// Deduplicating memos are queued for a check instead of propagating.
+    fn mark_observer_dirty(&self, observer_id: usize, wave: &mut Wave) {
+        let memo = self.with_context(|ctx| {
+            // If it's a memo, mark it as dirty and collect its dependents
+            let dirty = ctx.memo_dirty.get_mut(&observer_id)?;
+            if *dirty {
+                return Some(MemoDirty::Already);
+            }
+            *dirty = true;
+            let dependents = ctx.dependents(observer_id);
+            let hook = ctx.memo_hooks.get(&observer_id).and_then(Weak::upgrade);
+            Some(match hook {
+                Some(hook) if !dependents.is_empty() => MemoDirty::Check(hook, dependents),
+                _ => MemoDirty::Propagate(dependents),
+            })
+        });
+
+        let dependents = match memo {
+            None => {
+                if !wave.effects.contains(&observer_id) {
+                    wave.effects.push(observer_id);
                 }
-                return effects_to_run;
+                return;
+            }
+            Some(MemoDirty::Already) => return,
+            Some(MemoDirty::Propagate(dependents)) => dependents,
+            // A deduplicating memo recomputes after the pass, stopping there
+            // if unchanged
+            Some(MemoDirty::Check(hook, dependents)) => {
+                wave.checks.push_back((observer_id, hook, dependents));
+                return;
             }
+        };
+        for dependent_id in dependents {
+            self.mark_observer_dirty(dependent_id, wave);
+        }
+    }
 
-            // If it's an effect, collect it for execution
-            if let Some(effect) = ctx_ref.observers.get(&observer_id) {
-                effects_to_run.push(effect.clone());
+    /// Run an effect now, or queue it if the current thread is batching.
+    fn schedule_effect(&self, observer_id: usize) {
+        let deferred = BATCH.with(|batch| {
+            let mut batch = batch.borrow_mut();
+            if batch.depth == 0 {
+                return false;
             }
+            if !batch.pending.contains(&observer_id) {
+                batch.pending.push(observer_id);
+            }
+            true
+        });
+        if !deferred {
+            self.run_observer(observer_id);
+        }
+    }
 
-            effects_to_run
+    /// Run an observer now, returning whether it is still registered.
+    ///
+    /// A paused observer is not run; the notification is remembered instead.
+    pub(crate) fn run_observer(&self, observer_id: usize) -> bool {
+        let effect = self.with_context(|ctx| {
+            let effect = ctx.observers.get(&observer_id).cloned();
+            if let Some(missed) = ctx.paused.get_mut(&observer_id) {
+                *missed = true;
+                return effect.map(|_| None);
+            }
+            let effect = effect.map(Some);
+            #[cfg(feature = "metrics")]
+            if let (Some(metrics), Some(_)) = (ctx.metrics.as_mut(), &effect) {
+                metrics.record_run(observer_id);
+            }
+            effect
         });
 
-        // Execute effects outside of the borrow
-        for effect in effects_to_run {
-            effect();
+        // Execute effects outside of the lock, never inside the writer's
+        // tracking context
+        match effect {
+            Some(Some(effect)) => {
+                let handler = self.error_handler.read().unwrap().clone();
+                match handler {
+                    None => self.untracked(|| effect()),
+                    Some(handler) => {
+                        let run = panic::catch_unwind(AssertUnwindSafe(|| {
+                            self.untracked(|| effect());
+                        }));
+                        if let Err(payload) = run {
+                            handler(observer_id, payload);
+                        }
+                    }
+                }
+                true
+            }
+            Some(None) => true,
+            None => false,
         }
     }
 
+    /// Stop running an observer until it is resumed.
+    pub(crate) fn pause_observer(&self, observer_id: usize) {
+        self.with_context(|ctx| {
+            ctx.paused.entry(observer_id).or_insert(false);
+        });
+    }
+
+    /// Resume a paused observer, running it once if it missed a notification.
+    pub(crate) fn resume_observer(&self, observer_id: usize) {
+        let missed = self.with_context(|ctx| ctx.paused.remove(&observer_id));
+        if missed == Some(true) {
+            self.schedule_effect(observer_id);
+        }
+    }
+
+    /// Check whether an observer is still registered.
+    pub(crate) fn has_observer(&self, observer_id: usize) -> bool {
+        self.with_context(|ctx| ctx.observers.contains_key(&observer_id))
+    }
+
+    /// Check whether an observer is paused.
+    pub(crate) fn is_observer_paused(&self, observer_id: usize) -> bool {
+        self.with_context(|ctx| ctx.paused.contains_key(&observer_id))
+    }
+
+    /// Register a cleanup for the observer that is currently running.
+    ///
+    /// It runs before the observer's next run or when it is removed. Outside
+    /// of any observer the cleanup is dropped without running.
+    pub(crate) fn on_cleanup(&self, cleanup: impl FnOnce() + Send + 'static) {
+        if let Some(observer_id) = CURRENT_OBSERVER.with(Cell::get) {
+            self.with_context(|ctx| {
+                ctx.cleanups
+                    .entry(observer_id)
+                    .or_default()
+                    .push(Box::new(cleanup));
+            });
+        }
+    }
+
+    /// Run an observer's pending cleanups, newest first.
+    pub(crate) fn run_cleanups(&self, observer_id: usize) {
+        let cleanups = self.with_context(|ctx| ctx.cleanups.remove(&observer_id));
+        for cleanup in cleanups.into_iter().flatten().rev() {
+            cleanup();
+        }
+    }
+
+    /// Remove an observer from the graph.
+    pub(crate) fn remove_observer(&self, observer_id: usize) {
+        self.inner.read().unwrap().remove_observer(observer_id);
+    }
+
+    /// Run a function with effect execution deferred until it returns.
+    ///
+    /// Writes inside the batch still mark memos dirty immediately, but each
+    /// affected effect runs once after the outermost batch on this thread
+    /// completes.
+    ///
+    /// The batch's writes are also atomic to
+    /// [`with_snapshot`](Self::with_snapshot), which sees all of them or none.
+    pub fn batch<R>(&self, f: impl FnOnce() -> R) -> R {
+        let result = {
+            let _gate = self.gate.write();
+            let _depth = BatchDepth::enter();
+            f()
+        };
+
+        // Flush once the outermost batch has finished
+        let (deliveries, pending) = BATCH.with(|batch| {
+            let mut batch = batch.borrow_mut();
+            if batch.depth == 0 {
+                (
+                    std::mem::take(&mut batch.deliveries),
+                    std::mem::take(&mut batch.pending),
+                )
+            } else {
+                (Vec::new(), Vec::new())
+            }
+        });
+        for (_, deliver) in deliveries {
+            deliver();
+        }
+        for observer_id in pending {
+            self.run_observer(observer_id);
+        }
+        result
+    }
+
+    /// Queue `deliver` to run when the outermost batch on this thread ends.
+    ///
+    /// Hands `deliver` back without queueing when the thread is not batching.
+    /// Only the first delivery per `key` is kept, so repeated notifications
+    /// within a batch coalesce into one; deliveries without a key are all
+    /// kept. Deliveries run in queue order, before the batched effects.
+    pub(crate) fn defer_delivery<F>(&self, key: Option<usize>, deliver: F) -> Option<F>
+    where
+        F: FnOnce() + 'static,
+    {
+        BATCH.with(|batch| {
+            let mut batch = batch.borrow_mut();
+            if batch.depth == 0 {
+                return Some(deliver);
+            }
+            if key.is_none() || !batch.deliveries.iter().any(|(queued, _)| *queued == key) {
+                batch.deliveries.push((key, Box::new(deliver)));
+            }
+            None
+        })
+    }
+
+    /// Run a function that sees every value of this runtime at one instant.
+    ///
+    /// Writes to this runtime's signals and stores wait until `f` returns,
+    /// and `f` waits for running writes and batches to finish first, so all
+    /// reads inside see values from the same logical instant. Snapshots do
+    /// not block each other.
+    ///
+    /// # Panics
+    ///
+    /// Panics if called inside a batch, or if `f` writes to this runtime.
+    pub fn with_snapshot<R>(&self, f: impl FnOnce() -> R) -> R {
+        let _gate = self.gate.snapshot();
+        f()
+    }
+
+    /// Hold off snapshots of this runtime while a write is applied.
+    pub(crate) fn write_gate(&self) -> GateGuard<'_> {
+        self.gate.write()
+    }
+
     /// Run a function as an observer, tracking all reads.
     pub fn create_observer<F>(&self, observer_id: usize, f: F)
     where
         F: Fn() + Send + Sync + 'static,
     {
-        CONTEXT.with(|ctx| {
-            let mut ctx = ctx.lock().unwrap();
+        let replaced = self.with_context(|ctx| {
             // Clear old dependencies for this observer
-            if let Some(old_deps) = ctx.observer_deps.remove(&observer_id) {
-                for signal_id in old_deps {
-                    if let Some(deps) = ctx.dependencies.get_mut(&signal_id) {
-                        deps.remove(&observer_id);
-                    }
-                }
-            }
+            ctx.clear_dependencies(observer_id);
             // Store the observer effect
-            ctx.observers.insert(observer_id, Arc::new(f));
+            ctx.observers.insert(observer_id, Arc::new(f))
         });
+        drop(replaced);
     }
 
     /// Run a function with a specific observer as the current context.
@@ -164,66 +750,377 @@ impl ReactiveRuntime {
     where
         F: FnOnce() -> R,
     {
-        CONTEXT.with(|ctx| {
-            let prev = ctx.lock().unwrap().current_observer.replace(observer_id);
-            let result = f();
-            ctx.lock().unwrap().current_observer = prev;
-            result
-        })
+        ObserverScope::enter(Some(observer_id)).run(f)
+    }
+
+    /// Run a function with no current observer, so its reads are not tracked.
+    pub(crate) fn untracked<R>(&self, f: impl FnOnce() -> R) -> R {
+        ObserverScope::enter(None).run(f)
     }
 
     /// Register a memo and mark it as clean initially.
     pub fn register_memo(&self, memo_id: usize) {
-        CONTEXT.with(|ctx| {
-            ctx.lock().unwrap().memo_dirty.insert(memo_id, true);
+        self.with_context(|ctx| {
+            ctx.memo_dirty.insert(memo_id, true);
+        });
+    }
+
+    /// Let a memo recompute eagerly when dirtied, gating its dependents.
+    ///
+    /// The memo owns the hook; the graph only keeps a weak reference.
+    pub(crate) fn set_memo_hook(&self, memo_id: usize, hook: &MemoHook) {
+        self.with_context(|ctx| {
+            ctx.memo_hooks.insert(memo_id, Arc::downgrade(hook));
+        });
+    }
+
+    /// Get told when a source gains its first dependent or loses its last.
+    ///
+    /// The source owns the hook; the graph only keeps a weak reference. The
+    /// hook runs outside the graph's lock, right after the read or removal
+    /// causing the change.
+    pub(crate) fn set_observed_hook(&self, source_id: usize, hook: &ObservedHook) {
+        self.with_context(|ctx| {
+            ctx.observed_hooks.insert(source_id, Arc::downgrade(hook));
         });
     }
 
+    /// Re-run an observer, replacing the dependencies of its last run.
+    ///
+    /// Sources read by both runs keep the observer as a dependent
+    /// throughout, so they never appear unobserved in between.
+    pub(crate) fn retrack<R>(&self, observer_id: usize, f: impl FnOnce() -> R) -> R {
+        let previous = self.with_context(|ctx| ctx.observer_deps.remove(&observer_id));
+        let result = self.with_observer(observer_id, f);
+        if let Some(previous) = previous {
+            self.with_context(|ctx| {
+                let registered = ctx.observers.contains_key(&observer_id);
+                let current = ctx.observer_deps.get(&observer_id);
+                let stale: Vec<usize> = previous
+                    .into_iter()
+                    .filter(|id| !registered || !current.is_some_and(|deps| deps.contains(id)))
+                    .collect();
+                for source_id in stale {
+                    ctx.remove_dependent(source_id, observer_id);
+                }
+            });
+        }
+        result
+    }
+
     /// Check if a memo is dirty (needs recomputation).
     pub fn is_memo_dirty(&self, memo_id: usize) -> bool {
-        CONTEXT.with(|ctx| {
-            ctx.lock()
-                .unwrap()
-                .memo_dirty
-                .get(&memo_id)
-                .copied()
-                .unwrap_or(true)
-        })
+        self.with_context(|ctx| ctx.memo_dirty.get(&memo_id).copied().unwrap_or(true))
     }
 
     /// Mark a memo as clean (after recomputation).
     pub fn mark_memo_clean(&self, memo_id: usize) {
-        CONTEXT.with(|ctx| {
-            ctx.lock().unwrap().memo_dirty.insert(memo_id, false);
+        self.with_context(|ctx| {
+            ctx.memo_dirty.insert(memo_id, false);
         });
     }
 }
 
-// Thread-local reactive context for tracking dependencies.
+/// Run a function in a batch on the current runtime.
+///
+/// See [`ReactiveRuntime::batch`].
+pub fn batch<R>(f: impl FnOnce() -> R) -> R {
+    ReactiveRuntime::current().batch(f)
+}
+
+/// Run a function with a consistent view of the current runtime.
+///
+/// See [`ReactiveRuntime::with_snapshot`].
+pub fn with_snapshot<R>(f: impl FnOnce() -> R) -> R {
+    ReactiveRuntime::current().with_snapshot(f)
+}
+
+/// Run a function without tracking any of its reads as dependencies.
+///
+/// Inside an effect or memo, signals, memos and stores read by `f` do not
+/// become dependencies; the current observer is restored when `f` returns or
+/// unwinds. Use [`Signal::peek`](crate::Signal::peek) for a single read.
+pub fn untrack<R>(f: impl FnOnce() -> R) -> R {
+    ObserverScope::enter(None).run(f)
+}
+
+/// Wrap `f` so it runs in the runtime that is current now, on any thread.
+///
+/// Useful for handing work to thread pools or executors from inside a
+/// [`ReactiveRuntime::scope`]; see [`ReactiveRuntime::spawn_scoped`] for
+/// plain threads.
+pub fn run_in_current_runtime<F, R>(f: F) -> impl FnOnce() -> R + Send + 'static
+where
+    F: FnOnce() -> R + Send + 'static,
+{
+    let runtime = ReactiveRuntime::current();
+    move || runtime.with_runtime(f)
+}
+
+/// Get the graph statistics of the current runtime.
+///
+/// See [`ReactiveRuntime::stats`].
+pub fn stats() -> RuntimeStats {
+    ReactiveRuntime::current().stats()
+}
+
+// Thread-local tracking state; the dependency graph itself lives in the runtime.
 thread_local! {
-    static CONTEXT: Mutex<ReactiveContext> = Mutex::new(ReactiveContext::new());
+    static RUNTIME_STACK: RefCell<Vec<Arc<ReactiveRuntime>>> = const { RefCell::new(Vec::new()) };
+    static STRICT: Cell<bool> = const { Cell::new(false) };
+    static CURRENT_OBSERVER: Cell<Option<usize>> = const { Cell::new(None) };
+    static CREATED: RefCell<Vec<Vec<usize>>> = const { RefCell::new(Vec::new()) };
+    static BATCH: RefCell<BatchState> = const {
+        RefCell::new(BatchState {
+            depth: 0,
+            pending: Vec::new(),
+            deliveries: Vec::new(),
+        })
+    };
+}
+
+/// Pops the runtime pushed by `with_runtime`, even if the closure panics.
+struct ScopeGuard;
+
+impl Drop for ScopeGuard {
+    fn drop(&mut self) {
+        let popped = RUNTIME_STACK.with(|stack| stack.borrow_mut().pop());
+        // Drop outside the borrow, the runtime may own primitives that reenter
+        drop(popped);
+    }
+}
+
+/// Pops the frame pushed by `track_created`, even if the closure panics.
+///
+/// The frame's IDs are passed on to the enclosing frame either way, so an
+/// outer caller can still dispose what was created before the panic.
+struct CreatedScope {
+    exited: bool,
+}
+
+impl CreatedScope {
+    fn enter() -> Self {
+        CREATED.with(|created| created.borrow_mut().push(Vec::new()));
+        CreatedScope { exited: false }
+    }
+
+    fn exit(mut self) -> Vec<usize> {
+        self.exited = true;
+        Self::pop()
+    }
+
+    fn pop() -> Vec<usize> {
+        CREATED.with(|created| {
+            let mut created = created.borrow_mut();
+            let ids = created.pop().unwrap_or_default();
+            if let Some(parent) = created.last_mut() {
+                parent.extend_from_slice(&ids);
+            }
+            ids
+        })
+    }
+}
+
+impl Drop for CreatedScope {
+    fn drop(&mut self) {
+        if !self.exited {
+            Self::pop();
+        }
+    }
+}
+
+struct BatchState {
+    depth: usize,
+    // Effects queued while batching, in first-notified order
+    pending: Vec<usize>,
+    // Deliveries queued while batching, at most one per key
+    deliveries: Vec<(Option<usize>, Delivery)>,
+}
+
+type Delivery = Box<dyn FnOnce()>;
+
+/// Sets the current observer, restoring the previous one even on unwind.
+struct ObserverScope {
+    prev: Option<usize>,
+}
+
+impl ObserverScope {
+    fn enter(observer: Option<usize>) -> Self {
+        let prev = CURRENT_OBSERVER.with(|current| current.replace(observer));
+        ObserverScope { prev }
+    }
+
+    fn run<R>(self, f: impl FnOnce() -> R) -> R {
+        f()
+    }
+}
+
+impl Drop for ObserverScope {
+    fn drop(&mut self) {
+        CURRENT_OBSERVER.with(|current| current.set(self.prev));
+    }
+}
+
+/// Keeps the batch depth balanced even if the batched closure panics.
+struct BatchDepth;
+
+impl BatchDepth {
+    fn enter() -> Self {
+        BATCH.with(|batch| batch.borrow_mut().depth += 1);
+        BatchDepth
+    }
+}
+
+impl Drop for BatchDepth {
+    fn drop(&mut self) {
+        let dropped = BATCH.with(|batch| {
+            let mut batch = batch.borrow_mut();
+            batch.depth -= 1;
+            if batch.depth == 0 && std::thread::panicking() {
+                batch.pending.clear();
+                std::mem::take(&mut batch.deliveries)
+            } else {
+                Vec::new()
+            }
+        });
+        // Dropped outside the borrow, the deliveries may own primitives
+        drop(dropped);
+    }
 }
 
 struct ReactiveContext {
-    current_observer: Option<usize>,
     // Map from signal ID to set of observer IDs that depend on it
     dependencies: HashMap<usize, HashSet<usize>>,
     // Map from observer ID to set of signal IDs it depends on
     observer_deps: HashMap<usize, HashSet<usize>>,
     // Map from observer ID to the effect function
-    observers: HashMap<usize, Arc<dyn Fn() + Send + Sync>>,
+    observers: HashMap<usize, Observer>,
     // Map from memo ID to dirty state
     memo_dirty: HashMap<usize, bool>,
+    // Map from paused observer ID to whether it missed a notification
+    paused: HashMap<usize, bool>,
+    // Map from observer ID to the filter deciding which reads it tracks
+    read_filters: HashMap<usize, Weak<dyn Fn(usize) -> bool + Send + Sync>>,
+    // Map from observer ID to cleanups registered by its last run, oldest first
+    cleanups: HashMap<usize, Vec<Cleanup>>,
+    // Map from primitive ID to its display name
+    names: HashMap<usize, String>,
+    // Map from memo ID to its eager recompute hook, for deduplicating memos
+    memo_hooks: HashMap<usize, Weak<dyn Fn() -> bool + Send + Sync>>,
+    // Map from source ID to the hook told when it becomes (un)observed
+    observed_hooks: HashMap<usize, Weak<dyn Fn(bool) + Send + Sync>>,
+    // Observed-state changes of hooked sources not yet reported
+    transitions: Vec<(usize, bool)>,
+    // Write and run counts, present while metrics are enabled
+    #[cfg(feature = "metrics")]
+    metrics: Option<Metrics>,
 }
 
 impl ReactiveContext {
     fn new() -> Self {
         Self {
-            current_observer: None,
             dependencies: HashMap::new(),
             observer_deps: HashMap::new(),
             observers: HashMap::new(),
             memo_dirty: HashMap::new(),
+            paused: HashMap::new(),
+            read_filters: HashMap::new(),
+            memo_hooks: HashMap::new(),
+            observed_hooks: HashMap::new(),
+            transitions: Vec::new(),
+            names: HashMap::new(),
+            cleanups: HashMap::new(),
+            #[cfg(feature = "metrics")]
+            metrics: None,
+        }
+    }
+
+    /// Observers that currently depend on a source.
+    fn dependents(&self, source_id: usize) -> Vec<usize> {
+        self.dependencies
+            .get(&source_id)
+            .map(|deps| deps.iter().copied().collect())
+            .unwrap_or_default()
+    }
+
+    fn add_dependency(&mut self, signal_id: usize, observer_id: usize) {
+        // Add dependency: signal -> observer
+        let dependents = self.dependencies.entry(signal_id).or_default();
+        if dependents.insert(observer_id)
+            && dependents.len() == 1
+            && self.observed_hooks.contains_key(&signal_id)
+        {
+            self.transitions.push((signal_id, true));
+        }
+        // Track that this observer depends on this signal
+        self.observer_deps
+            .entry(observer_id)
+            .or_default()
+            .insert(signal_id);
+    }
+
+    fn clear_dependencies(&mut self, observer_id: usize) {
+        if let Some(old_deps) = self.observer_deps.remove(&observer_id) {
+            for signal_id in old_deps {
+                self.remove_dependent(signal_id, observer_id);
+            }
+        }
+    }
+
+    /// Remove `observer_id` from the dependents of `source_id` only.
+    fn remove_dependent(&mut self, source_id: usize, observer_id: usize) {
+        if let Some(deps) = self.dependencies.get_mut(&source_id) {
+            if deps.remove(&observer_id)
+                && deps.is_empty()
+                && self.observed_hooks.contains_key(&source_id)
+            {
+                self.transitions.push((source_id, false));
+            }
+        }
+    }
+
+    /// Take the pending transitions whose hooks are still alive.
+    fn take_transitions(&mut self) -> Transitions {
+        if self.transitions.is_empty() {
+            return Transitions(Vec::new());
+        }
+        let transitions = std::mem::take(&mut self.transitions);
+        let hooks = &self.observed_hooks;
+        Transitions(
+            transitions
+                .into_iter()
+                .filter_map(|(source_id, observed)| {
+                    let hook = hooks.get(&source_id)?.upgrade()?;
+                    Some((hook, observed))
+                })
+                .collect(),
+        )
+    }
+
+    fn remove_observer(&mut self, observer_id: usize) -> Removed {
+        self.clear_dependencies(observer_id);
+        self.paused.remove(&observer_id);
+        self.read_filters.remove(&observer_id);
+        Removed {
+            observer: self.observers.remove(&observer_id),
+            cleanups: self.cleanups.remove(&observer_id).unwrap_or_default(),
+        }
+    }
+
+    /// Remove a primitive from the graph, both as an observer and as a source.
+    fn dispose(&mut self, id: usize) -> Removed {
+        let removed = self.remove_observer(id);
+        self.memo_dirty.remove(&id);
+        self.memo_hooks.remove(&id);
+        self.observed_hooks.remove(&id);
+        self.names.remove(&id);
+        if let Some(observers) = self.dependencies.remove(&id) {
+            for observer_id in observers {
+                if let Some(deps) = self.observer_deps.get_mut(&observer_id) {
+                    deps.remove(&id);
+                }
+            }
         }
+        removed
     }
 }
@@ -0,0 +1,30 @@
+use std::collections::HashMap;
+
+/// Write and run counts collected by the runtime while metrics are enabled.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Metrics {
+    /// Number of writes per signal ID.
+    pub signal_writes: HashMap<usize, u64>,
+    /// Number of runtime-triggered runs per observer ID.
+    pub observer_runs: HashMap<usize, u64>,
+}
+
+impl Metrics {
+    /// Get the number of recorded writes for a signal.
+    pub fn writes(&self, signal_id: usize) -> u64 {
+        self.signal_writes.get(&signal_id).copied().unwrap_or(0)
+    }
+
+    /// Get the number of recorded runs for an observer.
+    pub fn runs(&self, observer_id: usize) -> u64 {
+        self.observer_runs.get(&observer_id).copied().unwrap_or(0)
+    }
+
+    pub(crate) fn record_write(&mut self, signal_id: usize) {
+        *self.signal_writes.entry(signal_id).or_insert(0) += 1;
+    }
+
+    pub(crate) fn record_run(&mut self, observer_id: usize) {
+        *self.observer_runs.entry(observer_id).or_insert(0) += 1;
+    }
+}
@@ -1,3 +1,15 @@
 mod context;
+#[cfg(feature = "metrics")]
+mod metrics;
+mod owner;
+mod pool;
+mod snapshot;
+mod stats;
 
-pub(crate) use context::{ReactiveRuntime, RuntimeInner};
+pub use context::{batch, run_in_current_runtime, stats, untrack, with_snapshot, ReactiveRuntime};
+pub(crate) use context::{MemoHook, ObservedHook, ReadFilter, RuntimeInner};
+#[cfg(feature = "metrics")]
+pub use metrics::Metrics;
+pub use owner::Owner;
+pub(crate) use pool::ThreadPool;
+pub use stats::RuntimeStats;
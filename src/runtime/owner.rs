@@ -0,0 +1,100 @@
+use super::ReactiveRuntime;
+use crate::{Effect, Memo, Signal};
+use std::sync::{Arc, Mutex};
+
+/// An explicit owner of reactive primitives, disposed all at once.
+///
+/// Signals, effects and memos created through the owner are registered in
+/// the runtime that was current when the owner was created, along with any
+/// primitive they create on construction. [`dispose`](Self::dispose), or
+/// dropping the owner, removes all of them from the reactive graph: effects
+/// stop running and signals stop notifying. Handles to disposed signals and
+/// memos stay valid but are no longer reactive.
+pub struct Owner {
+    runtime: Arc<ReactiveRuntime>,
+    ids: Mutex<Vec<usize>>,
+    effects: Mutex<Vec<Effect>>,
+}
+
+impl Owner {
+    /// Create an owner in the current runtime.
+    pub fn new() -> Self {
+        Self {
+            runtime: ReactiveRuntime::current(),
+            ids: Mutex::new(Vec::new()),
+            effects: Mutex::new(Vec::new()),
+        }
+    }
+
+    /// Create a signal owned by this owner.
+    pub fn signal<T>(&self, initial: T) -> Signal<T>
+    where
+        T: Clone + Send + Sync + 'static,
+    {
+        self.create(|| Signal::new(initial))
+    }
+
+    /// Create an effect owned by this owner, returning its ID.
+    ///
+    /// The owner keeps the effect alive until it is disposed.
+    pub fn effect<F>(&self, f: F) -> usize
+    where
+        F: Fn() + Send + Sync + 'static,
+    {
+        let effect = self.create(|| Effect::new(f));
+        let id = effect.id();
+        self.effects.lock().unwrap().push(effect);
+        id
+    }
+
+    /// Create a memo owned by this owner.
+    pub fn memo<T, F>(&self, f: F) -> Memo<T>
+    where
+        T: Clone + 'static,
+        F: Fn() -> T + Send + Sync + 'static,
+    {
+        self.create(|| Memo::new(f))
+    }
+
+    /// Get the number of primitives owned, including nested ones.
+    pub fn len(&self) -> usize {
+        self.ids.lock().unwrap().len()
+    }
+
+    /// Check whether the owner owns nothing.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Remove every owned primitive from the runtime.
+    ///
+    /// The owner is left empty and can create new primitives afterwards.
+    pub fn dispose(&self) {
+        let ids = std::mem::take(&mut *self.ids.lock().unwrap());
+        let effects = std::mem::take(&mut *self.effects.lock().unwrap());
+        self.runtime.dispose_ids(&ids);
+        // Drop the effects outside the locks, their cleanups may reenter
+        drop(effects);
+    }
+
+    fn create<R>(&self, f: impl FnOnce() -> R) -> R {
+        let mut created = None;
+        let ids = self
+            .runtime
+            .with_runtime(|| self.runtime.track_created(|| created = Some(f())));
+        self.ids.lock().unwrap().extend(ids);
+        created.expect("created inside track_created")
+    }
+}
+
+impl Default for Owner {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Drop for Owner {
+    fn drop(&mut self) {
+        self.dispose();
+    }
+}
@@ -0,0 +1,49 @@
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::mpsc::{self, Sender};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::thread;
+
+type Job = Box<dyn FnOnce() + Send>;
+
+/// Shared worker threads for background effects.
+pub(crate) struct ThreadPool {
+    sender: Mutex<Sender<Job>>,
+}
+
+impl ThreadPool {
+    /// Get the pool, starting one worker per available core on first use.
+    pub(crate) fn global() -> &'static ThreadPool {
+        static POOL: OnceLock<ThreadPool> = OnceLock::new();
+        POOL.get_or_init(|| {
+            let workers = thread::available_parallelism().map_or(2, |n| n.get());
+            ThreadPool::new(workers)
+        })
+    }
+
+    fn new(workers: usize) -> Self {
+        let (sender, receiver) = mpsc::channel::<Job>();
+        let receiver = Arc::new(Mutex::new(receiver));
+        for index in 0..workers {
+            let receiver = Arc::clone(&receiver);
+            thread::Builder::new()
+                .name(format!("tincan-worker-{index}"))
+                .spawn(move || loop {
+                    let job = match receiver.lock().unwrap().recv() {
+                        Ok(job) => job,
+                        Err(_) => break,
+                    };
+                    // A panicking effect must not take the worker down with it
+                    let _ = panic::catch_unwind(AssertUnwindSafe(job));
+                })
+                .expect("failed to spawn tincan worker thread");
+        }
+        Self {
+            sender: Mutex::new(sender),
+        }
+    }
+
+    /// Queue a job to run on one of the workers.
+    pub(crate) fn execute(&self, job: impl FnOnce() + Send + 'static) {
+        let _ = self.sender.lock().unwrap().send(Box::new(job));
+    }
+}
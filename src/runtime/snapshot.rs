@@ -0,0 +1,126 @@
+use std::cell::RefCell;
+use std::sync::{Condvar, Mutex};
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Hold {
+    Write,
+    Snapshot,
+}
+
+// Gates held by this thread, innermost last, keyed by gate address
+thread_local! {
+    static HELD: RefCell<Vec<(usize, Hold)>> = const { RefCell::new(Vec::new()) };
+}
+
+#[derive(Default)]
+struct GateState {
+    writers: usize,
+    readers: usize,
+    // Snapshots waiting for writers to finish; new writers queue behind them
+    waiting: usize,
+}
+
+/// Excludes writes while a snapshot reads, see [`ReactiveRuntime::with_snapshot`].
+///
+/// Writers and snapshots only exclude each other: any number of writers, or
+/// any number of snapshots, can hold the gate at once. A thread already
+/// holding the gate re-enters it for free.
+///
+/// [`ReactiveRuntime::with_snapshot`]: super::ReactiveRuntime::with_snapshot
+#[derive(Default)]
+pub(crate) struct SnapshotGate {
+    state: Mutex<GateState>,
+    released: Condvar,
+}
+
+impl SnapshotGate {
+    /// Hold the gate for a write, waiting for running snapshots to finish.
+    ///
+    /// # Panics
+    ///
+    /// Panics if this thread is inside a snapshot of the same runtime.
+    pub(crate) fn write(&self) -> GateGuard<'_> {
+        match self.held() {
+            Some(Hold::Write) => return GateGuard { gate: None },
+            Some(Hold::Snapshot) => panic!("cannot write to a reactive value inside with_snapshot"),
+            None => {}
+        }
+        let state = self.state.lock().unwrap();
+        let mut state = self
+            .released
+            .wait_while(state, |state| state.readers > 0 || state.waiting > 0)
+            .unwrap();
+        state.writers += 1;
+        self.enter(Hold::Write)
+    }
+
+    /// Hold the gate for a snapshot, waiting for running writes to finish.
+    ///
+    /// # Panics
+    ///
+    /// Panics if this thread is writing to the same runtime, such as in a batch.
+    pub(crate) fn snapshot(&self) -> GateGuard<'_> {
+        match self.held() {
+            Some(Hold::Snapshot) => return GateGuard { gate: None },
+            Some(Hold::Write) => panic!("with_snapshot cannot be called while writing"),
+            None => {}
+        }
+        let mut state = self.state.lock().unwrap();
+        state.waiting += 1;
+        let mut state = self
+            .released
+            .wait_while(state, |state| state.writers > 0)
+            .unwrap();
+        state.waiting -= 1;
+        state.readers += 1;
+        self.enter(Hold::Snapshot)
+    }
+
+    fn key(&self) -> usize {
+        self as *const Self as usize
+    }
+
+    fn held(&self) -> Option<Hold> {
+        let key = self.key();
+        HELD.with(|held| {
+            held.borrow()
+                .iter()
+                .rev()
+                .find(|(gate, _)| *gate == key)
+                .map(|(_, hold)| *hold)
+        })
+    }
+
+    fn enter(&self, hold: Hold) -> GateGuard<'_> {
+        HELD.with(|held| held.borrow_mut().push((self.key(), hold)));
+        GateGuard { gate: Some(self) }
+    }
+}
+
+/// Releases a [`SnapshotGate`] when dropped, even on unwind.
+pub(crate) struct GateGuard<'a> {
+    // `None` when re-entered, the outer guard releases the gate
+    gate: Option<&'a SnapshotGate>,
+}
+
+impl Drop for GateGuard<'_> {
+    fn drop(&mut self) {
+        let Some(gate) = self.gate else {
+            return;
+        };
+        let key = gate.key();
+        let hold = HELD.with(|held| {
+            let mut held = held.borrow_mut();
+            let index = held.iter().rposition(|(gate, _)| *gate == key);
+            index.map(|index| held.remove(index).1)
+        });
+        let mut state = gate.state.lock().unwrap();
+        match hold {
+            Some(Hold::Write) => state.writers -= 1,
+            Some(Hold::Snapshot) => state.readers -= 1,
+            None => return,
+        }
+        drop(state);
+        gate.released.notify_all();
+    }
+}
@@ -0,0 +1,10 @@
+/// A snapshot of the size of a runtime's reactive graph.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct RuntimeStats {
+    /// Number of registered observers: effects, watchers and map sinks.
+    pub observers: usize,
+    /// Number of registered memos.
+    pub memos: usize,
+    /// Number of sources with at least one dependent.
+    pub tracked_sources: usize,
+}
@@ -0,0 +1,154 @@
+use std::ops::{Deref, DerefMut};
+
+// Storage for a signal's value. By default values live behind an `RwLock`.
+// With the `arc-swap` feature they live in an `ArcSwap` instead: reads load an
+// `Arc` without locking, and writes are serialized by a writer lock and
+// publish a new `Arc` when the write guard drops.
+
+#[cfg(not(feature = "arc-swap"))]
+mod imp {
+    use std::sync::{RwLock, RwLockReadGuard, RwLockWriteGuard};
+
+    pub(crate) struct SignalCell<T>(RwLock<T>);
+
+    pub(crate) struct CellRef<'a, T>(pub(super) RwLockReadGuard<'a, T>);
+
+    pub(crate) struct CellMut<'a, T>(pub(super) RwLockWriteGuard<'a, T>);
+
+    impl<T> SignalCell<T> {
+        pub(crate) fn new(value: T) -> Self {
+            Self(RwLock::new(value))
+        }
+
+        pub(crate) fn read(&self) -> CellRef<'_, T> {
+            CellRef(self.0.read().unwrap())
+        }
+
+        pub(crate) fn write(&self) -> CellMut<'_, T> {
+            CellMut(self.0.write().unwrap())
+        }
+    }
+
+    impl<T> CellRef<'_, T> {
+        pub(super) fn get(&self) -> &T {
+            &self.0
+        }
+    }
+
+    impl<T> CellMut<'_, T> {
+        pub(super) fn get(&self) -> &T {
+            &self.0
+        }
+
+        pub(super) fn get_mut(&mut self) -> &mut T {
+            &mut self.0
+        }
+
+        /// Overwrite the value without cloning the current one first.
+        pub(crate) fn set(&mut self, value: T) {
+            *self.0 = value;
+        }
+    }
+}
+
+#[cfg(feature = "arc-swap")]
+mod imp {
+    use arc_swap::{ArcSwap, Guard};
+    use std::sync::{Arc, Mutex, MutexGuard};
+
+    pub(crate) struct SignalCell<T> {
+        current: ArcSwap<T>,
+        writer: Mutex<()>,
+    }
+
+    pub(crate) struct CellRef<'a, T>(Guard<Arc<T>>, std::marker::PhantomData<&'a T>);
+
+    /// Pending write, published when dropped while still holding the writer lock.
+    pub(crate) struct CellMut<'a, T> {
+        cell: &'a SignalCell<T>,
+        current: Arc<T>,
+        // Copy-on-write: cloned from `current` on first mutable access
+        next: Option<T>,
+        _writer: MutexGuard<'a, ()>,
+    }
+
+    impl<T> SignalCell<T> {
+        pub(crate) fn new(value: T) -> Self {
+            Self {
+                current: ArcSwap::from_pointee(value),
+                writer: Mutex::new(()),
+            }
+        }
+
+        pub(crate) fn read(&self) -> CellRef<'_, T> {
+            CellRef(self.current.load(), std::marker::PhantomData)
+        }
+
+        pub(crate) fn write(&self) -> CellMut<'_, T> {
+            let writer = self.writer.lock().unwrap();
+            CellMut {
+                cell: self,
+                current: self.current.load_full(),
+                next: None,
+                _writer: writer,
+            }
+        }
+    }
+
+    impl<T> CellRef<'_, T> {
+        pub(super) fn get(&self) -> &T {
+            &self.0
+        }
+    }
+
+    impl<T> CellMut<'_, T> {
+        pub(super) fn get(&self) -> &T {
+            self.next.as_ref().unwrap_or(&self.current)
+        }
+
+        pub(super) fn get_mut(&mut self) -> &mut T
+        where
+            T: Clone,
+        {
+            let current = &self.current;
+            self.next.get_or_insert_with(|| T::clone(current))
+        }
+
+        /// Overwrite the value without cloning the current one first.
+        pub(crate) fn set(&mut self, value: T) {
+            self.next = Some(value);
+        }
+    }
+
+    impl<T> Drop for CellMut<'_, T> {
+        fn drop(&mut self) {
+            if let Some(next) = self.next.take() {
+                self.cell.current.store(Arc::new(next));
+            }
+        }
+    }
+}
+
+pub(crate) use imp::{CellMut, CellRef, SignalCell};
+
+impl<T> Deref for CellRef<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        self.get()
+    }
+}
+
+impl<T> Deref for CellMut<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        self.get()
+    }
+}
+
+impl<T: Clone> DerefMut for CellMut<'_, T> {
+    fn deref_mut(&mut self) -> &mut T {
+        self.get_mut()
+    }
+}
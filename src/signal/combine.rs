@@ -0,0 +1,87 @@
+use super::Signal;
+
+/// Combines a changing set of signals into one signal of their latest values.
+///
+/// Sources can be added and removed at any time. The combined signal lists
+/// the current value of every source, in the order they were added, and
+/// updates when any source changes or the set itself changes.
+pub struct DynamicCombine<T> {
+    members: Signal<Vec<Signal<T>>>,
+    combined: Signal<Vec<T>>,
+}
+
+impl<T: Clone + Send + Sync + 'static> DynamicCombine<T> {
+    /// Create a combination with no sources.
+    pub fn new() -> Self {
+        let members: Signal<Vec<Signal<T>>> = Signal::new(Vec::new());
+        // The driving effect re-tracks on every run, so it follows the set
+        let combined = members.map_tracked(|members| members.iter().map(Signal::get).collect());
+        Self { members, combined }
+    }
+
+    /// Add a source signal.
+    pub fn add(&self, signal: &Signal<T>) {
+        let signal = signal.clone();
+        self.members.update(|members| members.push(signal));
+    }
+
+    /// Remove the source with the given signal ID.
+    ///
+    /// Returns `false` if no source has that ID.
+    pub fn remove(&self, id: usize) -> bool {
+        let index = self
+            .members
+            .with(|members| members.iter().position(|member| member.id() == id));
+        match index {
+            Some(index) => {
+                self.members.update(|members| {
+                    members.remove(index);
+                });
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Get the combined signal of the latest source values.
+    pub fn signal(&self) -> Signal<Vec<T>> {
+        self.combined.clone()
+    }
+}
+
+impl<T: Clone + Send + Sync + 'static> Default for DynamicCombine<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Combine three signals into one signal of their latest values.
+///
+/// One effect reads all three sources, so unlike `a.zip(b).zip(c)` there are
+/// no intermediate signals or nested tuples.
+pub fn combine3<A, B, C>(a: Signal<A>, b: Signal<B>, c: Signal<C>) -> Signal<(A, B, C)>
+where
+    A: Clone + Send + Sync + 'static,
+    B: Clone + Send + Sync + 'static,
+    C: Clone + Send + Sync + 'static,
+{
+    Signal::computed(move || (a.get(), b.get(), c.get()))
+}
+
+/// Combine four signals into one signal of their latest values.
+///
+/// See [`combine3`].
+pub fn combine4<A, B, C, D>(
+    a: Signal<A>,
+    b: Signal<B>,
+    c: Signal<C>,
+    d: Signal<D>,
+) -> Signal<(A, B, C, D)>
+where
+    A: Clone + Send + Sync + 'static,
+    B: Clone + Send + Sync + 'static,
+    C: Clone + Send + Sync + 'static,
+    D: Clone + Send + Sync + 'static,
+{
+    Signal::computed(move || (a.get(), b.get(), c.get(), d.get()))
+}
@@ -1,3 +1,13 @@
+mod cell;
+mod combine;
+#[allow(clippy::module_inception)]
 mod signal;
+mod split;
+mod vec;
 
-pub use signal::{Signal, WatchGuard};
+pub use combine::{combine3, combine4, DynamicCombine};
+pub use signal::{
+    bind, Binding, DynSignal, FusedMap, MapError, PropagationHandle, Signal, WatchGuard,
+};
+pub use split::{create_signal, ReadSignal, WriteSignal};
+pub use vec::VecSignalExt;
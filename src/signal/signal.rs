@@ -1,11 +1,124 @@
-use crate::runtime::ReactiveRuntime;
-use std::sync::{Arc, RwLock, Weak};
+use super::cell::SignalCell;
+use crate::effect::{self, Effect};
+use crate::runtime::{ObservedHook, ReactiveRuntime, ThreadPool};
+use std::any::Any;
+use std::collections::{HashMap, VecDeque};
+use std::fmt;
+use std::hash::Hash;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Condvar, Mutex, OnceLock, RwLock, Weak};
+use std::thread;
+use std::time::{Duration, Instant};
 
 /// A reactive signal that holds a value and notifies subscribers when changed.
+///
+/// Values must be `Clone`, so polymorphic values are stored behind an `Arc`;
+/// see [`DynSignal`].
 #[derive(Clone)]
 pub struct Signal<T> {
-    value: Arc<RwLock<T>>,
+    value: Arc<SignalCell<T>>,
     id: usize,
+    runtime: Arc<ReactiveRuntime>,
+    // When the value was last written, for `age`
+    changed_at: Arc<Mutex<Instant>>,
+    // Effects driving a derived signal, kept alive as long as any handle is
+    _effects: Arc<Vec<Effect>>,
+    // Shared dispatcher driving every `map` of this signal
+    fanout: Arc<MapFanOut>,
+    // For a `map` of another signal, disposes the sink updating it
+    _sink: Option<Arc<MapSink>>,
+}
+
+/// A signal holding a shared trait object, such as `DynSignal<dyn Shape>`.
+///
+/// `get` clones the `Arc`, not the value behind it, and `map_tracked` can
+/// produce another trait object signal.
+pub type DynSignal<T> = Signal<Arc<T>>;
+
+/// Single watcher on a source that drives all of its mapped signals.
+///
+/// Each mapped signal registers a sink observer that is not a dependent of
+/// the source, so one write runs one watcher no matter how many maps exist.
+/// Shared by every handle to the source, so it drops with the last one.
+#[derive(Default)]
+struct MapFanOut {
+    sinks: Mutex<Vec<(Weak<ReactiveRuntime>, usize)>>,
+    watcher: Mutex<Option<WatchGuard>>,
+    // Observers from `map_weak` to dispose once the source is gone
+    weak_dependents: Mutex<Vec<(Weak<ReactiveRuntime>, usize)>>,
+    // Starts and stops the driver of a `map_lazy` signal; the graph holds it weakly
+    observed_hook: OnceLock<ObservedHook>,
+}
+
+impl Drop for MapFanOut {
+    fn drop(&mut self) {
+        let sinks = std::mem::take(self.sinks.get_mut().unwrap());
+        let dependents = std::mem::take(self.weak_dependents.get_mut().unwrap());
+        for (runtime, observer_id) in sinks.into_iter().chain(dependents) {
+            if let Some(runtime) = runtime.upgrade() {
+                runtime.dispose_ids(&[observer_id]);
+            }
+        }
+    }
+}
+
+/// Disposes the sink driving a mapped signal once its last handle drops.
+struct MapSink {
+    sink_id: usize,
+    fanout: Weak<MapFanOut>,
+    runtime: Weak<ReactiveRuntime>,
+}
+
+impl Drop for MapSink {
+    fn drop(&mut self) {
+        if let Some(fanout) = self.fanout.upgrade() {
+            fanout
+                .sinks
+                .lock()
+                .unwrap()
+                .retain(|(_, sink_id)| *sink_id != self.sink_id);
+        }
+        if let Some(runtime) = self.runtime.upgrade() {
+            runtime.dispose_ids(&[self.sink_id]);
+        }
+    }
+}
+
+/// Non-owning handle used by drivers that must not keep a signal alive.
+#[derive(Clone)]
+struct WeakSignal<T> {
+    value: Weak<SignalCell<T>>,
+    id: usize,
+    runtime: Arc<ReactiveRuntime>,
+    changed_at: Arc<Mutex<Instant>>,
+}
+
+impl<T> WeakSignal<T> {
+    /// Set the value, returning `false` if the signal has been dropped.
+    fn set(&self, new_value: T) -> bool {
+        let Some(value) = self.value.upgrade() else {
+            return false;
+        };
+        {
+            let _gate = self.runtime.write_gate();
+            value.write().set(new_value);
+        }
+        *self.changed_at.lock().unwrap() = Instant::now();
+        self.runtime.notify_observers(self.id);
+        true
+    }
+
+    /// Set the value without notifying, returning `false` if dropped.
+    fn set_quietly(&self, new_value: T) -> bool {
+        let Some(value) = self.value.upgrade() else {
+            return false;
+        };
+        let _gate = self.runtime.write_gate();
+        value.write().set(new_value);
+        *self.changed_at.lock().unwrap() = Instant::now();
+        true
+    }
 }
 
 impl<T: Clone + Send + Sync + 'static> Signal<T> {
@@ -15,39 +128,286 @@ impl<T: Clone + Send + Sync + 'static> Signal<T> {
         let id = runtime.next_id();
 
         Self {
-            value: Arc::new(RwLock::new(initial)),
+            value: Arc::new(SignalCell::new(initial)),
             id,
+            runtime,
+            changed_at: Arc::new(Mutex::new(Instant::now())),
+            _effects: Arc::default(),
+            fanout: Arc::default(),
+            _sink: None,
+        }
+    }
+
+    /// Create a signal driven by a predetermined sequence of values.
+    ///
+    /// The signal starts with the first item. Each call to the returned
+    /// `advance` closure sets the next item, returning `false` once the
+    /// sequence is exhausted. Panics if the sequence is empty.
+    #[allow(clippy::should_implement_trait)]
+    pub fn from_iter<I>(iter: I) -> (Signal<T>, impl FnMut() -> bool)
+    where
+        I: IntoIterator<Item = T>,
+    {
+        let mut iter = iter.into_iter();
+        let signal = Signal::new(iter.next().expect("sequence must not be empty"));
+        let driven = signal.clone();
+        let advance = move || match iter.next() {
+            Some(value) => {
+                driven.set(value);
+                true
+            }
+            None => false,
+        };
+        (signal, advance)
+    }
+
+    /// Create a signal whose value is computed by an effect it owns.
+    ///
+    /// `compute` runs inside the effect, so every read it makes is tracked.
+    /// The first result seeds the signal and later results are written to it.
+    pub(super) fn computed<F>(compute: F) -> Self
+    where
+        F: Fn() -> T + Send + Sync + 'static,
+    {
+        Self::computed_with(compute, Signal::set)
+    }
+
+    /// Like [`computed`](Self::computed), but an unchanged result is neither
+    /// written nor notified.
+    pub(crate) fn computed_deduped<F>(compute: F) -> Self
+    where
+        T: PartialEq,
+        F: Fn() -> T + Send + Sync + 'static,
+    {
+        Self::computed_with(compute, |derived, value| {
+            derived.set_if_changed(value);
+        })
+    }
+
+    fn computed_with<F, W>(compute: F, write: W) -> Self
+    where
+        F: Fn() -> T + Send + Sync + 'static,
+        W: Fn(&Signal<T>, T) + Send + Sync + 'static,
+    {
+        let slot = Arc::new(OnceLock::<Signal<T>>::new());
+        let effect = Effect::new({
+            let slot = Arc::clone(&slot);
+            move || {
+                let value = compute();
+                match slot.get() {
+                    Some(derived) => write(derived, value),
+                    None => {
+                        let _ = slot.set(Signal::new(value));
+                    }
+                }
+            }
+        });
+
+        // The effect only holds a bare handle, so owning it here is not a cycle
+        let derived = slot.get().expect("effects run on creation");
+        derived.owning(&Arc::new(vec![effect]))
+    }
+
+    /// Create a derived signal written only when `f` returns `Some`.
+    ///
+    /// If `f` rejects the current value, `seed` provides the initial value.
+    fn filtered<U, F, S>(&self, f: F, seed: S) -> Signal<U>
+    where
+        U: Clone + Send + Sync + 'static,
+        F: Fn(&T) -> Option<U> + Send + Sync + 'static,
+        S: FnOnce(&T) -> U + Send + 'static,
+    {
+        let source = self.clone();
+        let seed = Mutex::new(Some(seed));
+        let slot = Arc::new(OnceLock::<Signal<U>>::new());
+        let effect = Effect::new({
+            let slot = Arc::clone(&slot);
+            move || {
+                source.with(|value| match (slot.get(), f(value)) {
+                    (Some(derived), Some(next)) => derived.set(next),
+                    (Some(_), None) => {}
+                    (None, next) => {
+                        let initial = next.unwrap_or_else(|| {
+                            let seed = seed.lock().unwrap().take();
+                            seed.expect("seeded once")(value)
+                        });
+                        let _ = slot.set(Signal::new(initial));
+                    }
+                })
+            }
+        });
+
+        let derived = slot.get().expect("effects run on creation");
+        derived.owning(&Arc::new(vec![effect]))
+    }
+
+    /// Get a handle for writers that must not keep this signal alive.
+    fn downgrade(&self) -> WeakSignal<T> {
+        WeakSignal {
+            value: Arc::downgrade(&self.value),
+            id: self.id,
+            runtime: Arc::clone(&self.runtime),
+            changed_at: Arc::clone(&self.changed_at),
+        }
+    }
+
+    /// Get a handle to this signal that keeps the given driving effects alive.
+    fn owning(&self, effects: &Arc<Vec<Effect>>) -> Self {
+        Self {
+            value: Arc::clone(&self.value),
+            id: self.id,
+            runtime: Arc::clone(&self.runtime),
+            changed_at: Arc::clone(&self.changed_at),
+            _effects: Arc::clone(effects),
+            fanout: Arc::clone(&self.fanout),
+            _sink: self._sink.clone(),
         }
     }
 
     /// Get the current value of the signal.
     pub fn get(&self) -> T {
-        let runtime = ReactiveRuntime::current();
-        runtime.track_read(self.id);
-        self.value.read().unwrap().clone()
+        self.runtime.track_read(self.id);
+        self.value.read().clone()
     }
 
     /// Set a new value for the signal.
+    ///
+    /// If the runtime has a comparator registered for `T` and it reports the
+    /// new value equal to the current one, nothing is written or notified.
+    /// See [`ReactiveRuntime::register_equality`].
     pub fn set(&self, new_value: T) {
-        *self.value.write().unwrap() = new_value;
-        let runtime = ReactiveRuntime::current();
-        runtime.notify_observers(self.id);
+        let eq = self.runtime.equality::<T>();
+        let gate = self.runtime.write_gate();
+        let mut value = self.value.write();
+        if eq.is_some_and(|eq| eq(&value, &new_value)) {
+            return;
+        }
+        value.set(new_value);
+        drop(value);
+        drop(gate);
+        self.mark_changed();
+        self.runtime.notify_observers(self.id);
+    }
+
+    /// Set a new value only if it differs from the current one.
+    ///
+    /// Returns whether the value changed. Unlike [`set`](Self::set), an equal
+    /// value is neither written nor notified, which stops redundant
+    /// recomputation and feedback loops. The comparison is not tracked, so
+    /// an effect can call this without depending on `self`.
+    pub fn set_if_changed(&self, new_value: T) -> bool
+    where
+        T: PartialEq,
+    {
+        let changed = *self.value.read() != new_value;
+        if changed {
+            self.set(new_value);
+        }
+        changed
     }
 
     /// Update the value using a function.
     pub fn update(&self, f: impl FnOnce(&mut T)) {
-        let mut value = self.value.write().unwrap();
-        f(&mut *value);
+        self.update_returning(f);
+    }
+
+    /// Update the value using a function and return what it returns.
+    ///
+    /// Observers are notified before this returns, so the result can
+    /// describe the update, such as a vector's new length, without another
+    /// read.
+    pub fn update_returning<R>(&self, f: impl FnOnce(&mut T) -> R) -> R {
+        let gate = self.runtime.write_gate();
+        let mut value = self.value.write();
+        let result = f(&mut *value);
         drop(value); // Release the write lock before notifying
-        let runtime = ReactiveRuntime::current();
-        runtime.notify_observers(self.id);
+        drop(gate);
+        self.mark_changed();
+        self.runtime.notify_observers(self.id);
+        result
+    }
+
+    /// Set a new value and return the previous one.
+    ///
+    /// Always notifies, like [`update`](Self::update), even if the runtime
+    /// has an equality comparator for `T`.
+    pub fn replace(&self, new_value: T) -> T {
+        self.update_returning(|value| std::mem::replace(value, new_value))
+    }
+
+    /// Take the value, leaving `T::default()` in its place, and notify.
+    pub fn take(&self) -> T
+    where
+        T: Default,
+    {
+        self.replace(T::default())
+    }
+
+    /// Update the value in place, notifying only if it changed.
+    ///
+    /// Returns whether the value changed. The in-place counterpart of
+    /// [`set_if_changed`](Self::set_if_changed): the value is cloned before
+    /// `f` runs to compare against, so every call pays for one clone. For
+    /// large values prefer [`update_if_dirty`](Self::update_if_dirty).
+    pub fn update_checked(&self, f: impl FnOnce(&mut T)) -> bool
+    where
+        T: PartialEq,
+    {
+        self.update_if_dirty(|value| {
+            let before = value.clone();
+            f(value);
+            *value != before
+        })
+    }
+
+    /// Update the value in place, notifying only if `f` reports a change.
+    ///
+    /// `f` returns whether it modified the value, which is returned as well.
+    /// Nothing is cloned or compared, so a wrong answer from `f` either
+    /// skips a notification or sends a redundant one.
+    pub fn update_if_dirty(&self, f: impl FnOnce(&mut T) -> bool) -> bool {
+        let gate = self.runtime.write_gate();
+        let mut value = self.value.write();
+        let changed = f(&mut *value);
+        drop(value);
+        drop(gate);
+        if changed {
+            self.mark_changed();
+            self.runtime.notify_observers(self.id);
+        }
+        changed
+    }
+
+    /// Get the time since the value was last written.
+    ///
+    /// Counts from creation until the first write. Writes skipped as
+    /// unchanged, such as an equal [`set_if_changed`](Self::set_if_changed),
+    /// do not reset it.
+    pub fn age(&self) -> Duration {
+        self.changed_at.lock().unwrap().elapsed()
+    }
+
+    fn mark_changed(&self) {
+        *self.changed_at.lock().unwrap() = Instant::now();
     }
 
     /// Read the value with a function without cloning.
     pub fn with<R>(&self, f: impl FnOnce(&T) -> R) -> R {
-        let runtime = ReactiveRuntime::current();
-        runtime.track_read(self.id);
-        let value = self.value.read().unwrap();
+        self.runtime.track_read(self.id);
+        let value = self.value.read();
+        f(&*value)
+    }
+
+    /// Get the current value without tracking it as a dependency.
+    ///
+    /// An effect or memo calling this does not re-run when the signal changes.
+    pub fn peek(&self) -> T {
+        self.value.read().clone()
+    }
+
+    /// Read the value with a function without cloning or tracking it.
+    pub fn peek_with<R>(&self, f: impl FnOnce(&T) -> R) -> R {
+        let value = self.value.read();
         f(&*value)
     }
 
@@ -61,14 +421,14 @@ impl<T: Clone + Send + Sync + 'static> Signal<T> {
     where
         F: Fn(T) + Send + Sync + 'static,
     {
-        let runtime = ReactiveRuntime::current();
+        let runtime = &self.runtime;
         let observer_id = runtime.next_id();
         let value = Arc::clone(&self.value);
         let callback = Arc::new(callback);
         let callback_clone = Arc::clone(&callback);
 
         runtime.create_observer(observer_id, move || {
-            let val = value.read().unwrap().clone();
+            let val = value.read().clone();
             callback_clone(val);
         });
 
@@ -78,7 +438,7 @@ impl<T: Clone + Send + Sync + 'static> Signal<T> {
         });
 
         // Call immediately with current value
-        let val = self.value.read().unwrap().clone();
+        let val = self.value.read().clone();
         callback(val);
 
         WatchGuard {
@@ -87,31 +447,651 @@ impl<T: Clone + Send + Sync + 'static> Signal<T> {
         }
     }
 
+    /// Watch this signal with a callback that can mutate its own state.
+    ///
+    /// The callback is kept behind a `Mutex`, so it must not write to this
+    /// signal from inside the callback or it will deadlock.
+    pub fn watch_mut<F>(&self, callback: F) -> WatchGuard
+    where
+        F: FnMut(T) + Send + 'static,
+    {
+        let callback = Mutex::new(callback);
+        self.watch(move |value| (callback.lock().unwrap())(value))
+    }
+
+    /// Watch this signal with a callback that can cancel its own watcher.
+    ///
+    /// The callback receives a cancel function as its second argument. Once it
+    /// is called the watcher is removed and the callback never fires again,
+    /// even if the returned guard is still alive.
+    pub fn watch_cancellable<F>(&self, callback: F) -> WatchGuard
+    where
+        F: Fn(T, &dyn Fn()) + Send + Sync + 'static,
+    {
+        let cancelled = Arc::new(AtomicBool::new(false));
+        let observer_id = Arc::new(OnceLock::new());
+        let guard = self.watch({
+            let cancelled = Arc::clone(&cancelled);
+            let observer_id = Arc::clone(&observer_id);
+            let runtime = Arc::downgrade(&self.runtime);
+            move |value| {
+                if cancelled.load(Ordering::SeqCst) {
+                    return;
+                }
+                let cancel = || {
+                    cancelled.store(true, Ordering::SeqCst);
+                    if let (Some(&id), Some(runtime)) = (observer_id.get(), runtime.upgrade()) {
+                        runtime.remove_observer(id);
+                    }
+                };
+                callback(value, &cancel);
+            }
+        });
+
+        // The first call runs before the observer ID is known
+        let _ = observer_id.set(guard.observer_id);
+        if cancelled.load(Ordering::SeqCst) {
+            self.runtime.remove_observer(guard.observer_id);
+        }
+        guard
+    }
+
+    /// Watch this signal on a background thread, skipping superseded values.
+    ///
+    /// Values go through a single-slot mailbox: while the callback is busy,
+    /// each new value replaces the one waiting, so a slow callback always
+    /// processes the latest value next and never falls behind. Calls never
+    /// overlap. A value already waiting when the guard is dropped is still
+    /// delivered; use [`ReactiveRuntime::settle`] to wait for delivery.
+    pub fn watch_latest<F>(&self, callback: F) -> WatchGuard
+    where
+        F: Fn(T) + Send + Sync + 'static,
+    {
+        let callback = Arc::new(callback);
+        let mailbox = Arc::new(Mutex::new(Mailbox {
+            latest: None,
+            running: false,
+        }));
+        let runtime = Arc::clone(&self.runtime);
+        self.watch(move |value| {
+            {
+                let mut slot = mailbox.lock().unwrap();
+                // Replaces, and drops, any value the worker has not taken yet
+                slot.latest = Some(value);
+                if slot.running {
+                    return;
+                }
+                slot.running = true;
+            }
+            runtime.begin_work();
+            let worker = MailboxWorker {
+                runtime: Arc::clone(&runtime),
+                mailbox: Arc::clone(&mailbox),
+            };
+            let callback = Arc::clone(&callback);
+            ThreadPool::global().execute(move || loop {
+                let next = {
+                    let mut slot = worker.mailbox.lock().unwrap();
+                    match slot.latest.take() {
+                        Some(value) => value,
+                        None => {
+                            slot.running = false;
+                            break;
+                        }
+                    }
+                };
+                callback(next);
+            });
+        })
+    }
+
     /// Create a derived signal by applying a function to this signal's value.
-    pub fn map<U, F>(&self, f: F) -> (Signal<U>, WatchGuard)
+    ///
+    /// All maps of a signal share a single watcher on it. The derived signal
+    /// keeps updating until every handle to it is dropped; see
+    /// [`map_with_guard`](Self::map_with_guard) to stop it explicitly.
+    ///
+    /// ```
+    /// use tincan::Signal;
+    ///
+    /// let count = Signal::new(5);
+    /// let doubled = count.map(|n| n * 2);
+    /// count.set(10);
+    /// assert_eq!(doubled.get(), 20);
+    /// ```
+    pub fn map<U, F>(&self, f: F) -> Signal<U>
     where
         U: Clone + Send + Sync + 'static,
         F: Fn(&T) -> U + Send + Sync + 'static,
     {
-        let source = self.clone();
-        let derived = Signal::new(f(&self.get()));
-        let derived_clone = derived.clone();
-        let f = Arc::new(f);
-
-        // Watch the source and update the derived signal
-        std::mem::forget(source.watch(move |value| {
-            derived_clone.set(f(&value));
-        }));
+        self.map_sink(f).0
+    }
 
+    /// Like [`map`](Self::map), but also returns a guard that stops the
+    /// derived signal updating when dropped.
+    ///
+    /// ```
+    /// use tincan::Signal;
+    ///
+    /// let count = Signal::new(5);
+    /// let (doubled, guard) = count.map_with_guard(|n| n * 2);
+    /// drop(guard);
+    /// count.set(10);
+    /// assert_eq!(doubled.get(), 10);
+    /// ```
+    pub fn map_with_guard<U, F>(&self, f: F) -> (Signal<U>, WatchGuard)
+    where
+        U: Clone + Send + Sync + 'static,
+        F: Fn(&T) -> U + Send + Sync + 'static,
+    {
+        let (derived, sink_id) = self.map_sink(f);
         (
             derived,
             WatchGuard {
-                observer_id: source.id(),
-                runtime: Arc::downgrade(&ReactiveRuntime::current().inner()),
+                observer_id: sink_id,
+                runtime: Arc::downgrade(&self.runtime.inner()),
+            },
+        )
+    }
+
+    /// Register a map of this signal with its fan-out, returning the derived
+    /// signal and the id of the sink updating it.
+    fn map_sink<U, F>(&self, f: F) -> (Signal<U>, usize)
+    where
+        U: Clone + Send + Sync + 'static,
+        F: Fn(&T) -> U + Send + Sync + 'static,
+    {
+        let runtime = Arc::clone(&self.runtime);
+        // Untracked, so constructing a map never adds a dependency to
+        // whatever observer happens to be current; the sink tracks the source
+        let mut derived = Signal::new(self.peek_with(&f));
+
+        // The sink is an observer the dispatcher runs, not a source dependent
+        let sink_id = runtime.next_id();
+        let source = Arc::clone(&self.value);
+        let target = derived.downgrade();
+        runtime.create_observer(sink_id, {
+            let runtime = Arc::clone(&runtime);
+            move || {
+                let value = source.read().clone();
+                if !target.set(f(&value)) {
+                    runtime.dispose_ids(&[sink_id]);
+                }
+            }
+        });
+
+        let mut watcher = self.fanout.watcher.lock().unwrap();
+        if watcher.is_none() {
+            let fanout = Arc::downgrade(&self.fanout);
+            let runtime = Arc::clone(&runtime);
+            *watcher = Some(self.watch(move |_| {
+                if let Some(fanout) = fanout.upgrade() {
+                    fanout.dispatch(&runtime);
+                }
+            }));
+        }
+        drop(watcher);
+        self.fanout
+            .sinks
+            .lock()
+            .unwrap()
+            .push((Arc::downgrade(&runtime), sink_id));
+        derived._sink = Some(Arc::new(MapSink {
+            sink_id,
+            fanout: Arc::downgrade(&self.fanout),
+            runtime: Arc::downgrade(&runtime),
+        }));
+
+        (derived, sink_id)
+    }
+
+    /// Create a derived signal that only tracks this signal while observed.
+    ///
+    /// The driving effect is created when an effect, memo or watcher first
+    /// depends on the derived signal and dropped when the last one stops,
+    /// so unused chains of lazy maps cost nothing on writes. On becoming
+    /// observed again the value is recomputed before the new dependent reads
+    /// it. Reads outside any observer while unobserved return the last
+    /// computed value.
+    pub fn map_lazy<U, F>(&self, f: F) -> Signal<U>
+    where
+        U: Clone + Send + Sync + 'static,
+        F: Fn(&T) -> U + Send + Sync + 'static,
+    {
+        let f = Arc::new(f);
+        let derived = Signal::new(self.peek_with(&*f));
+        let source = self.clone();
+        let target = derived.downgrade();
+        let driver = Mutex::new(None::<Effect>);
+        let hook: ObservedHook = Arc::new(move |observed| {
+            if !observed {
+                // Drop outside the lock, stopping may cascade up a lazy chain
+                let stopped = driver.lock().unwrap().take();
+                drop(stopped);
+                return;
+            }
+            let mut driver = driver.lock().unwrap();
+            if driver.is_some() {
+                return;
+            }
+            let (source, f, target) = (source.clone(), Arc::clone(&f), target.clone());
+            let caught_up = AtomicBool::new(false);
+            let runtime = Arc::clone(&source.runtime);
+            *driver = Some(runtime.with_runtime(|| {
+                Effect::new(move || {
+                    let value = source.with(|value| f(value));
+                    // The new dependent has not read yet, so catching up
+                    // needs no notification
+                    if caught_up.swap(true, Ordering::SeqCst) {
+                        target.set(value);
+                    } else {
+                        target.set_quietly(value);
+                    }
+                })
+            }));
+        });
+        derived.runtime.set_observed_hook(derived.id, &hook);
+        let _ = derived.fanout.observed_hook.set(hook);
+        derived
+    }
+
+    /// Create a derived signal whose mapping function can read other signals.
+    ///
+    /// `f` runs inside the effect driving the derived signal, so any signal it
+    /// reads is tracked alongside the source and the derived value updates
+    /// when either changes. The derived signal owns that effect.
+    pub fn map_tracked<U, F>(&self, f: F) -> Signal<U>
+    where
+        U: Clone + Send + Sync + 'static,
+        F: Fn(&T) -> U + Send + Sync + 'static,
+    {
+        let source = self.clone();
+        Signal::computed(move || source.with(|value| f(value)))
+    }
+
+    /// Create a derived signal that also recomputes when any of `triggers`
+    /// changes.
+    ///
+    /// `triggers` are signal ids from [`id`](Self::id). Their values are not
+    /// read, so this suits maps over state the runtime cannot track that
+    /// another signal announces changes to.
+    pub fn map_with_triggers<U, F>(&self, triggers: Vec<usize>, f: F) -> Signal<U>
+    where
+        U: Clone + Send + Sync + 'static,
+        F: Fn(&T) -> U + Send + Sync + 'static,
+    {
+        let source = self.clone();
+        Signal::computed(move || {
+            for &trigger in &triggers {
+                source.runtime.track_read(trigger);
+            }
+            source.with(|value| f(value))
+        })
+    }
+
+    /// Create a derived signal that can also be recomputed on demand.
+    ///
+    /// For maps reading state the runtime cannot track, such as a clock or
+    /// a global cache. Besides every change of this signal, calling the
+    /// returned `refresh` re-runs `f` on the current value.
+    pub fn map_manual<U, F>(&self, f: F) -> (Signal<U>, impl Fn() + Send + Sync + 'static)
+    where
+        U: Clone + Send + Sync + 'static,
+        F: Fn(&T) -> U + Send + Sync + 'static,
+    {
+        let refreshes = Signal::new(0u64);
+        let source = self.clone();
+        let derived = Signal::computed({
+            let refreshes = refreshes.clone();
+            move || {
+                refreshes.with(|_| ());
+                source.with(|value| f(value))
+            }
+        });
+        (derived, move || {
+            refreshes.update(|n| *n = n.wrapping_add(1))
+        })
+    }
+
+    /// Start a chain of pure maps that is fused into a single derived signal.
+    ///
+    /// `map_fused(f).then(g).build()` is driven by one effect computing
+    /// `g(f(x))`, instead of one node and one effect per step. Intermediate
+    /// values are not observable; use separate maps if you need them.
+    pub fn map_fused<U, F>(&self, f: F) -> FusedMap<T, U>
+    where
+        F: Fn(&T) -> U + Send + Sync + 'static,
+    {
+        FusedMap {
+            source: self.clone(),
+            f: Arc::new(f),
+        }
+    }
+
+    /// Create a derived signal whose driving effect holds this signal weakly.
+    ///
+    /// Unlike the other maps, the derived signal does not keep the source
+    /// alive, so the two can reference each other without leaking. Once every
+    /// handle to the source is dropped the effect is torn down and the derived
+    /// signal keeps its last value.
+    pub fn map_weak<U, F>(&self, f: F) -> Signal<U>
+    where
+        U: Clone + Send + Sync + 'static,
+        F: Fn(&T) -> U + Send + Sync + 'static,
+    {
+        let source = Arc::downgrade(&self.value);
+        let source_id = self.id;
+        let slot = Arc::new(OnceLock::<Signal<U>>::new());
+        let effect = Effect::new({
+            let runtime = Arc::clone(&self.runtime);
+            let slot = Arc::clone(&slot);
+            move || {
+                let Some(value) = source.upgrade() else {
+                    return;
+                };
+                runtime.track_read(source_id);
+                let mapped = f(&value.read());
+                match slot.get() {
+                    Some(derived) => derived.set(mapped),
+                    None => {
+                        let _ = slot.set(Signal::new(mapped));
+                    }
+                }
+            }
+        });
+        self.fanout
+            .weak_dependents
+            .lock()
+            .unwrap()
+            .push((Arc::downgrade(&self.runtime), effect.id()));
+
+        let derived = slot.get().expect("effects run on creation");
+        derived.owning(&Arc::new(vec![effect]))
+    }
+
+    /// Create a derived signal that captures every failure of `f` as a value.
+    ///
+    /// Errors returned by `f` become [`MapError::Failed`] and panics become
+    /// [`MapError::Panic`], so a failing map never takes the driving effect
+    /// down and downstream code handles both through one `Result`.
+    pub fn map_catching<U, E, F>(&self, f: F) -> Signal<Result<U, MapError<E>>>
+    where
+        U: Clone + Send + Sync + 'static,
+        E: Clone + Send + Sync + 'static,
+        F: Fn(&T) -> Result<U, E> + Send + Sync + 'static,
+    {
+        self.map_tracked(
+            move |value| match panic::catch_unwind(AssertUnwindSafe(|| f(value))) {
+                Ok(result) => result.map_err(MapError::Failed),
+                Err(payload) => Err(MapError::Panic(panic_message(payload.as_ref()))),
             },
         )
     }
 
+    /// Create a derived signal whose propagation can be paused and resumed.
+    ///
+    /// While paused the derived signal keeps its last value; on resume it
+    /// catches up to the latest source value once, if the source changed.
+    pub fn map_controlled<U, F>(&self, f: F) -> (Signal<U>, PropagationHandle)
+    where
+        U: Clone + Send + Sync + 'static,
+        F: Fn(&T) -> U + Send + Sync + 'static,
+    {
+        let derived = self.map_tracked(f);
+        let handle = PropagationHandle {
+            observer_id: derived._effects[0].id(),
+            runtime: Arc::downgrade(&derived.runtime),
+        };
+        (derived, handle)
+    }
+
+    /// Create a derived signal holding the last value that passed `pred`.
+    ///
+    /// Values failing `pred` are skipped, so downstream observers only see
+    /// passing ones. If the current value fails `pred` there is nothing to
+    /// seed with, and the derived signal starts with it anyway; use
+    /// [`filter_or`](Self::filter_or) to start from a known-good value instead.
+    pub fn filter<P>(&self, pred: P) -> Signal<T>
+    where
+        P: Fn(&T) -> bool + Send + Sync + 'static,
+    {
+        self.filtered(move |value| pred(value).then(|| value.clone()), T::clone)
+    }
+
+    /// Like [`filter`](Self::filter), starting from `initial` when the current
+    /// value fails `pred`.
+    pub fn filter_or<P>(&self, initial: T, pred: P) -> Signal<T>
+    where
+        P: Fn(&T) -> bool + Send + Sync + 'static,
+    {
+        self.filtered(move |value| pred(value).then(|| value.clone()), |_| initial)
+    }
+
+    /// Create a derived signal that transforms values and skips some of them.
+    ///
+    /// The derived signal is only written when `f` returns `Some`. If `f`
+    /// returns `None` for the current value there is nothing to seed with, so
+    /// the derived signal starts at `U::default()`.
+    pub fn filter_map<U, F>(&self, f: F) -> Signal<U>
+    where
+        U: Clone + Default + Send + Sync + 'static,
+        F: Fn(&T) -> Option<U> + Send + Sync + 'static,
+    {
+        self.filtered(f, |_| U::default())
+    }
+
+    /// Create a derived signal that follows this one until `f` returns `None`.
+    ///
+    /// Like [`Iterator::map_while`]: values map through `f` while it returns
+    /// `Some`. The first `None` disposes the driving effect, so the derived
+    /// signal keeps the last mapped value and this signal no longer drives it.
+    /// Returns `None` if `f` already returns `None` for the current value, as
+    /// there is no value to start from.
+    pub fn map_while<U, F>(&self, f: F) -> Option<Signal<U>>
+    where
+        U: Clone + Send + Sync + 'static,
+        F: Fn(&T) -> Option<U> + Send + Sync + 'static,
+    {
+        let source = self.clone();
+        let slot = Arc::new(OnceLock::<Signal<U>>::new());
+        let observer_id = Arc::new(OnceLock::new());
+        let effect = Effect::new({
+            let runtime = Arc::downgrade(&self.runtime);
+            let slot = Arc::clone(&slot);
+            let observer_id = Arc::clone(&observer_id);
+            move || match (source.with(&f), slot.get()) {
+                (Some(value), Some(derived)) => derived.set(value),
+                (Some(value), None) => {
+                    let _ = slot.set(Signal::new(value));
+                }
+                (None, None) => {}
+                (None, Some(_)) => {
+                    if let (Some(&id), Some(runtime)) = (observer_id.get(), runtime.upgrade()) {
+                        runtime.remove_observer(id);
+                    }
+                }
+            }
+        });
+
+        // The first run happens before the effect ID is known; if it mapped
+        // nothing, dropping the effect here disposes it
+        let _ = observer_id.set(effect.id());
+        let derived = slot.get()?;
+        Some(derived.owning(&Arc::new(vec![effect])))
+    }
+
+    /// Create a signal that follows this one once it has been quiet for `dur`.
+    ///
+    /// Each change restarts the timer, so a burst of writes collapses into a
+    /// single update carrying the final value. The timer runs on a dedicated
+    /// thread, which is why `T` must be `Send + 'static`; the thread exits
+    /// once every handle to the returned signal is dropped.
+    pub fn debounce(&self, dur: Duration) -> Signal<T> {
+        let derived = Signal::new(self.peek());
+        let timer = Arc::new(DebounceTimer {
+            state: Mutex::new(DebounceState {
+                pending: None,
+                closed: false,
+            }),
+            changed: Condvar::new(),
+        });
+
+        let target = derived.downgrade();
+        thread::Builder::new()
+            .name("tincan-debounce".into())
+            .spawn({
+                let timer = Arc::clone(&timer);
+                move || {
+                    while let Some(value) = timer.next() {
+                        if !target.set(value) {
+                            break;
+                        }
+                    }
+                }
+            })
+            .expect("failed to spawn tincan debounce thread");
+
+        // The effect owns the closer, so dropping the derived signal stops the thread
+        let closer = DebounceCloser(timer);
+        let effect = effect::on_deferred(
+            {
+                let source = self.clone();
+                move || source.get()
+            },
+            move |value| closer.0.push(value, dur),
+        );
+        derived.owning(&Arc::new(vec![effect]))
+    }
+
+    /// Create a signal that follows this one at most once per `dur`.
+    ///
+    /// The first change after a quiet period is passed on immediately and
+    /// opens a window of `dur`. Changes inside the window are held back, and
+    /// the latest of them is passed on when the window ends, opening the next
+    /// one. Like [`debounce`](Self::debounce) the windows are timed on a
+    /// dedicated thread, which exits once every handle to the returned signal
+    /// is dropped.
+    pub fn throttle(&self, dur: Duration) -> Signal<T> {
+        let derived = Signal::new(self.peek());
+        let throttle = Arc::new(Throttle {
+            state: Mutex::new(ThrottleState {
+                window_end: None,
+                trailing: None,
+                closed: false,
+            }),
+            changed: Condvar::new(),
+        });
+
+        thread::Builder::new()
+            .name("tincan-throttle".into())
+            .spawn({
+                let throttle = Arc::clone(&throttle);
+                let target = derived.downgrade();
+                move || {
+                    while let Some(value) = throttle.next_trailing(dur) {
+                        if !target.set(value) {
+                            break;
+                        }
+                    }
+                }
+            })
+            .expect("failed to spawn tincan throttle thread");
+
+        let closer = ThrottleCloser(throttle);
+        let target = derived.downgrade();
+        let effect = effect::on_deferred(
+            {
+                let source = self.clone();
+                move || source.get()
+            },
+            move |value| {
+                if let Some(leading) = closer.0.offer(value, dur) {
+                    target.set(leading);
+                }
+            },
+        );
+        derived.owning(&Arc::new(vec![effect]))
+    }
+
+    /// Create a signal that is set to this one's value every `interval`.
+    ///
+    /// Unlike [`debounce`](Self::debounce) and [`throttle`](Self::throttle)
+    /// this ignores changes: a timer thread reads the current value each tick,
+    /// without tracking, and writes it even if it did not change. The thread
+    /// exits at the first tick after every handle to the returned signal is
+    /// dropped.
+    pub fn sample_every(&self, interval: Duration) -> Signal<T> {
+        let derived = Signal::new(self.peek());
+        let source = self.clone();
+        let target = derived.downgrade();
+        thread::Builder::new()
+            .name("tincan-sample".into())
+            .spawn(move || loop {
+                thread::sleep(interval);
+                if !target.set(source.peek()) {
+                    break;
+                }
+            })
+            .expect("failed to spawn tincan sample thread");
+        derived
+    }
+
+    /// Create a derived signal that maps values while carrying state.
+    ///
+    /// On every source change `f` updates the state and returns the next
+    /// output, like a Mealy machine. The state lives in the driving effect and
+    /// `f` also runs once for the current value.
+    pub fn map_scan<S, U, F>(&self, init: S, f: F) -> Signal<U>
+    where
+        S: Send + 'static,
+        U: Clone + Send + Sync + 'static,
+        F: Fn(&mut S, &T) -> U + Send + Sync + 'static,
+    {
+        let source = self.clone();
+        let state = Mutex::new(init);
+        Signal::computed(move || {
+            let mut state = state.lock().unwrap();
+            source.with(|value| f(&mut state, value))
+        })
+    }
+
+    /// Create a derived signal that folds every value of this signal into an
+    /// accumulator.
+    ///
+    /// Starting from `init`, each source value produces the next accumulator
+    /// from the previous one. Like [`map_scan`](Self::map_scan), `f` also runs
+    /// once for the current value.
+    pub fn scan<U, F>(&self, init: U, f: F) -> Signal<U>
+    where
+        U: Clone + Send + Sync + 'static,
+        F: Fn(&U, &T) -> U + Send + Sync + 'static,
+    {
+        self.map_scan(init, move |acc, value| {
+            *acc = f(acc, value);
+            acc.clone()
+        })
+    }
+
+    /// Create a derived signal holding the last `n` values of this signal.
+    ///
+    /// The oldest value is dropped once the buffer holds `n` values. The buffer
+    /// starts with the current value and lives in the driving effect.
+    pub fn history(&self, n: usize) -> Signal<VecDeque<T>> {
+        let source = self.clone();
+        let buffer = Mutex::new(VecDeque::with_capacity(n));
+        Signal::computed(move || {
+            let value = source.get();
+            let mut buffer = buffer.lock().unwrap();
+            if n > 0 {
+                if buffer.len() == n {
+                    buffer.pop_front();
+                }
+                buffer.push_back(value);
+            }
+            buffer.clone()
+        })
+    }
+
     /// Combine two signals into one using a function.
     pub fn zip<U>(self, other: Signal<U>) -> Signal<(T, U)>
     where
@@ -137,6 +1117,452 @@ impl<T: Clone + Send + Sync + 'static> Signal<T> {
     }
 }
 
+impl<A, B> Signal<(A, B)>
+where
+    A: Clone + PartialEq + Send + Sync + 'static,
+    B: Clone + PartialEq + Send + Sync + 'static,
+{
+    /// Split a tuple signal into one signal per component, the inverse of `zip`.
+    ///
+    /// Both signals share one effect reading this signal. A component is only
+    /// written when its value changed, so changing one side of the tuple does
+    /// not notify observers of the other.
+    pub fn unzip(&self) -> (Signal<A>, Signal<B>) {
+        let source = self.clone();
+        let slot = Arc::new(OnceLock::<(Signal<A>, Signal<B>)>::new());
+        let effect = Effect::new({
+            let slot = Arc::clone(&slot);
+            move || {
+                let (a, b) = source.get();
+                match slot.get() {
+                    Some((first, second)) => {
+                        first.set_if_changed(a);
+                        second.set_if_changed(b);
+                    }
+                    None => {
+                        let _ = slot.set((Signal::new(a), Signal::new(b)));
+                    }
+                }
+            }
+        });
+
+        let (first, second) = slot.get().expect("effects run on creation");
+        let effects = Arc::new(vec![effect]);
+        (first.owning(&effects), second.owning(&effects))
+    }
+}
+
+impl<T: Clone + Ord + Send + Sync + 'static> Signal<T> {
+    /// Create a signal that is `true` while this signal is greater than `other`.
+    pub fn gt(&self, other: &Signal<T>) -> Signal<bool> {
+        let (a, b) = (self.clone(), other.clone());
+        Signal::computed(move || a.with(|a| b.with(|b| a > b)))
+    }
+
+    /// Create a signal holding the smaller of this signal and `other`.
+    pub fn min_with(&self, other: &Signal<T>) -> Signal<T> {
+        let (a, b) = (self.clone(), other.clone());
+        Signal::computed(move || a.with(|a| b.with(|b| a.min(b).clone())))
+    }
+
+    /// Create a signal holding this signal's value restricted to `lo..=hi`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `lo > hi`, like [`Ord::clamp`].
+    pub fn clamp(&self, lo: T, hi: T) -> Signal<T> {
+        assert!(lo <= hi, "clamp bounds are out of order");
+        let source = self.clone();
+        Signal::computed(move || source.get().clamp(lo.clone(), hi.clone()))
+    }
+}
+
+impl<T: Clone + Send + Sync + 'static> Signal<Vec<T>> {
+    /// Create an empty vector signal with room for at least `capacity` items.
+    pub fn vec_with_capacity(capacity: usize) -> Self {
+        Signal::new(Vec::with_capacity(capacity))
+    }
+
+    /// Create a signal of the elements mapped and reduced in parallel.
+    ///
+    /// On every change the driving effect maps each element with `map` and
+    /// folds the results with `reduce`, starting from clones of `identity`,
+    /// on rayon's global thread pool. The effect blocks until the pool is
+    /// done, so `map` and `reduce` must be `Send + Sync` and `reduce` should
+    /// be associative for the result to be deterministic.
+    #[cfg(feature = "rayon")]
+    pub fn par_map_reduce<U, M, R>(&self, map: M, reduce: R, identity: U) -> Signal<U>
+    where
+        U: Clone + Send + Sync + 'static,
+        M: Fn(&T) -> U + Send + Sync + 'static,
+        R: Fn(U, U) -> U + Send + Sync + 'static,
+    {
+        use rayon::prelude::*;
+
+        let source = self.clone();
+        Signal::computed(move || {
+            source.with(|items| {
+                items
+                    .par_iter()
+                    .map(&map)
+                    .reduce(|| identity.clone(), &reduce)
+            })
+        })
+    }
+}
+
+impl<K, V> Signal<HashMap<K, V>>
+where
+    K: Clone + Eq + Hash + Send + Sync + 'static,
+    V: Clone + Send + Sync + 'static,
+{
+    /// Create an empty map signal with room for at least `capacity` entries.
+    pub fn map_with_capacity(capacity: usize) -> Self {
+        Signal::new(HashMap::with_capacity(capacity))
+    }
+}
+
+impl Signal<bool> {
+    /// Keep a guard alive only while this signal is `true`.
+    ///
+    /// `f` is called to create the guard (typically an [`Effect`](crate::Effect))
+    /// each time the signal becomes `true`, and the guard is dropped as soon as
+    /// the signal becomes `false` again or the returned `WatchGuard` is dropped.
+    pub fn when<F, G>(&self, f: F) -> WatchGuard
+    where
+        F: Fn() -> G + Send + Sync + 'static,
+        G: Send + 'static,
+    {
+        let active = Mutex::new(None);
+        self.watch(move |enabled| {
+            let mut active = active.lock().unwrap();
+            if enabled {
+                if active.is_none() {
+                    *active = Some(f());
+                }
+            } else {
+                // Tear down outside the lock in case the guard's drop reenters
+                let previous = active.take();
+                drop(active);
+                drop(previous);
+            }
+        })
+    }
+}
+
+/// Serializes only the current value, untracked. The reactive graph around
+/// the signal (its id, maps and dependents) is not serialized.
+#[cfg(feature = "serde")]
+impl<T> serde::Serialize for Signal<T>
+where
+    T: Clone + Send + Sync + serde::Serialize + 'static,
+{
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.peek_with(|value| value.serialize(serializer))
+    }
+}
+
+/// Deserializes a value into a fresh [`Signal::new`] in the current runtime.
+#[cfg(feature = "serde")]
+impl<'de, T> serde::Deserialize<'de> for Signal<T>
+where
+    T: Clone + Send + Sync + serde::Deserialize<'de> + 'static,
+{
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        T::deserialize(deserializer).map(Signal::new)
+    }
+}
+
+/// Keep two signals equal, writing each side's changes to the other.
+///
+/// `b` is set to `a`'s value first. A write is only forwarded when the other
+/// side holds a different value, so updates never loop. Dropping the returned
+/// guard ends the binding.
+pub fn bind<T>(a: &Signal<T>, b: &Signal<T>) -> Binding
+where
+    T: Clone + PartialEq + Send + Sync + 'static,
+{
+    let forward = |to: &Signal<T>| {
+        let to = to.clone();
+        move |value: T| {
+            to.set_if_changed(value);
+        }
+    };
+    Binding {
+        _a_to_b: a.watch(forward(b)),
+        _b_to_a: b.watch(forward(a)),
+    }
+}
+
+/// Timer shared by a [`Signal::debounce`] effect and its thread.
+struct DebounceTimer<T> {
+    state: Mutex<DebounceState<T>>,
+    changed: Condvar,
+}
+
+struct DebounceState<T> {
+    // The latest value and when it may be delivered
+    pending: Option<(T, Instant)>,
+    closed: bool,
+}
+
+impl<T> DebounceTimer<T> {
+    /// Queue `value`, replacing any pending one and restarting the timer.
+    fn push(&self, value: T, dur: Duration) {
+        self.state.lock().unwrap().pending = Some((value, Instant::now() + dur));
+        self.changed.notify_all();
+    }
+
+    /// Wait for a value to go quiet, or return `None` once closed.
+    fn next(&self) -> Option<T> {
+        let mut state = self.state.lock().unwrap();
+        loop {
+            if state.closed {
+                return None;
+            }
+            match state.pending.as_ref().map(|(_, due)| *due) {
+                None => state = self.changed.wait(state).unwrap(),
+                Some(due) => {
+                    let now = Instant::now();
+                    if now >= due {
+                        return state.pending.take().map(|(value, _)| value);
+                    }
+                    state = self.changed.wait_timeout(state, due - now).unwrap().0;
+                }
+            }
+        }
+    }
+}
+
+/// Closes a debounce timer when the driving effect is dropped.
+struct DebounceCloser<T>(Arc<DebounceTimer<T>>);
+
+impl<T> Drop for DebounceCloser<T> {
+    fn drop(&mut self) {
+        self.0.state.lock().unwrap().closed = true;
+        self.0.changed.notify_all();
+    }
+}
+
+/// Window state shared by a [`Signal::throttle`] effect and its thread.
+struct Throttle<T> {
+    state: Mutex<ThrottleState<T>>,
+    changed: Condvar,
+}
+
+struct ThrottleState<T> {
+    // End of the open window, if any
+    window_end: Option<Instant>,
+    // Latest value held back during the window
+    trailing: Option<T>,
+    closed: bool,
+}
+
+impl<T> Throttle<T> {
+    /// Offer a new value, returning it if it may be passed on right away.
+    fn offer(&self, value: T, dur: Duration) -> Option<T> {
+        let mut state = self.state.lock().unwrap();
+        let now = Instant::now();
+        if state.window_end.is_some_and(|end| now < end) {
+            state.trailing = Some(value);
+            return None;
+        }
+        state.window_end = Some(now + dur);
+        drop(state);
+        self.changed.notify_all();
+        Some(value)
+    }
+
+    /// Wait for a window holding a value to end, or return `None` once closed.
+    ///
+    /// Passing on a trailing value opens the next window.
+    fn next_trailing(&self, dur: Duration) -> Option<T> {
+        let mut state = self.state.lock().unwrap();
+        loop {
+            if state.closed {
+                return None;
+            }
+            match state.window_end {
+                None => state = self.changed.wait(state).unwrap(),
+                Some(end) => {
+                    let now = Instant::now();
+                    if now < end {
+                        state = self.changed.wait_timeout(state, end - now).unwrap().0;
+                        continue;
+                    }
+                    match state.trailing.take() {
+                        Some(value) => {
+                            state.window_end = Some(now + dur);
+                            return Some(value);
+                        }
+                        None => state.window_end = None,
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Closes a throttle when the driving effect is dropped.
+struct ThrottleCloser<T>(Arc<Throttle<T>>);
+
+impl<T> Drop for ThrottleCloser<T> {
+    fn drop(&mut self) {
+        self.0.state.lock().unwrap().closed = true;
+        self.0.changed.notify_all();
+    }
+}
+
+/// Single-slot mailbox of a [`Signal::watch_latest`] watcher.
+struct Mailbox<T> {
+    // The newest value not yet taken by the worker
+    latest: Option<T>,
+    running: bool,
+}
+
+/// A queued mailbox drain, reporting completion to its runtime when dropped.
+struct MailboxWorker<T> {
+    runtime: Arc<ReactiveRuntime>,
+    mailbox: Arc<Mutex<Mailbox<T>>>,
+}
+
+impl<T> Drop for MailboxWorker<T> {
+    fn drop(&mut self) {
+        // A panicking callback must not leave the mailbox stuck as running
+        if std::thread::panicking() {
+            let mut slot = self.mailbox.lock().unwrap_or_else(|e| e.into_inner());
+            slot.running = false;
+            slot.latest = None;
+        }
+        self.runtime.end_work();
+    }
+}
+
+/// Guard for a two-way binding created by [`bind`].
+pub struct Binding {
+    _a_to_b: WatchGuard,
+    _b_to_a: WatchGuard,
+}
+
+impl MapFanOut {
+    /// Run every registered sink, dropping the ones that no longer exist.
+    fn dispatch(&self, runtime: &ReactiveRuntime) {
+        let sinks: Vec<usize> = self
+            .sinks
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|(_, id)| *id)
+            .collect();
+        let removed: Vec<usize> = sinks
+            .into_iter()
+            .filter(|&sink_id| !runtime.run_observer(sink_id))
+            .collect();
+        if !removed.is_empty() {
+            self.sinks
+                .lock()
+                .unwrap()
+                .retain(|(_, sink_id)| !removed.contains(sink_id));
+        }
+    }
+}
+
+/// Builder for a chain of fused maps, created by [`Signal::map_fused`].
+pub struct FusedMap<T, U> {
+    source: Signal<T>,
+    f: Arc<dyn Fn(&T) -> U + Send + Sync>,
+}
+
+impl<T, U> FusedMap<T, U>
+where
+    T: Clone + Send + Sync + 'static,
+    U: 'static,
+{
+    /// Append another map to the chain.
+    pub fn then<V, G>(self, g: G) -> FusedMap<T, V>
+    where
+        G: Fn(&U) -> V + Send + Sync + 'static,
+    {
+        let f = self.f;
+        FusedMap {
+            source: self.source,
+            f: Arc::new(move |value| g(&f(value))),
+        }
+    }
+
+    /// Create the derived signal, driven by a single effect.
+    pub fn build(self) -> Signal<U>
+    where
+        U: Clone + Send + Sync,
+    {
+        let f = self.f;
+        self.source.map_tracked(move |value| f(value))
+    }
+}
+
+/// A failure captured by [`Signal::map_catching`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum MapError<E> {
+    /// The mapping function returned an error.
+    Failed(E),
+    /// The mapping function panicked with this message.
+    Panic(String),
+}
+
+impl<E: fmt::Display> fmt::Display for MapError<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MapError::Failed(error) => write!(f, "map failed: {error}"),
+            MapError::Panic(message) => write!(f, "map panicked: {message}"),
+        }
+    }
+}
+
+impl<E: fmt::Debug + fmt::Display> std::error::Error for MapError<E> {}
+
+/// Get the message of a caught panic payload.
+fn panic_message(payload: &(dyn Any + Send)) -> String {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        message.to_string()
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message.clone()
+    } else {
+        "unknown panic payload".to_string()
+    }
+}
+
+/// Pauses and resumes the effect driving a signal from
+/// [`Signal::map_controlled`].
+///
+/// The handle does not keep the derived signal alive.
+pub struct PropagationHandle {
+    observer_id: usize,
+    runtime: Weak<ReactiveRuntime>,
+}
+
+impl PropagationHandle {
+    /// Stop propagating source changes to the derived signal.
+    pub fn pause(&self) {
+        if let Some(runtime) = self.runtime.upgrade() {
+            runtime.pause_observer(self.observer_id);
+        }
+    }
+
+    /// Resume propagation, catching up once if the source changed.
+    pub fn resume(&self) {
+        if let Some(runtime) = self.runtime.upgrade() {
+            runtime.resume_observer(self.observer_id);
+        }
+    }
+
+    /// Check whether propagation is paused.
+    pub fn is_paused(&self) -> bool {
+        self.runtime
+            .upgrade()
+            .is_some_and(|runtime| runtime.is_observer_paused(self.observer_id))
+    }
+}
+
 /// RAII guard for signal watchers.
 pub struct WatchGuard {
     observer_id: usize,
@@ -146,7 +1572,7 @@ pub struct WatchGuard {
 impl Drop for WatchGuard {
     fn drop(&mut self) {
         if let Some(runtime) = self.runtime.upgrade() {
-            if let Ok(mut runtime) = runtime.write() {
+            if let Ok(runtime) = runtime.read() {
                 runtime.remove_observer(self.observer_id);
             }
         }
@@ -0,0 +1,99 @@
+use super::{Signal, WatchGuard};
+
+/// The read half of a signal, see [`create_signal`].
+///
+/// Reads track dependencies exactly like the same methods on [`Signal`].
+#[derive(Clone)]
+pub struct ReadSignal<T> {
+    signal: Signal<T>,
+}
+
+/// The write half of a signal, see [`create_signal`].
+#[derive(Clone)]
+pub struct WriteSignal<T> {
+    signal: Signal<T>,
+}
+
+/// Create a signal split into separate read and write handles.
+///
+/// This is [`Signal::new`] followed by [`Signal::split`]. Use [`Signal`]
+/// itself where one owner both reads and writes; hand out the halves to
+/// give other code only the access it needs.
+pub fn create_signal<T>(initial: T) -> (ReadSignal<T>, WriteSignal<T>)
+where
+    T: Clone + Send + Sync + 'static,
+{
+    Signal::new(initial).split()
+}
+
+impl<T: Clone + Send + Sync + 'static> Signal<T> {
+    /// Split this signal into read and write handles to the same value.
+    ///
+    /// Either half converts back into a full [`Signal`] with
+    /// [`ReadSignal::into_signal`] or [`WriteSignal::into_signal`].
+    pub fn split(self) -> (ReadSignal<T>, WriteSignal<T>) {
+        (
+            ReadSignal {
+                signal: self.clone(),
+            },
+            WriteSignal { signal: self },
+        )
+    }
+}
+
+impl<T: Clone + Send + Sync + 'static> ReadSignal<T> {
+    /// Get the current value of the signal.
+    pub fn get(&self) -> T {
+        self.signal.get()
+    }
+
+    /// Read the value with a function without cloning.
+    pub fn with<R>(&self, f: impl FnOnce(&T) -> R) -> R {
+        self.signal.with(f)
+    }
+
+    /// Get the current value without tracking it as a dependency.
+    pub fn peek(&self) -> T {
+        self.signal.peek()
+    }
+
+    /// Watch the signal for changes.
+    pub fn watch<F>(&self, callback: F) -> WatchGuard
+    where
+        F: Fn(T) + Send + Sync + 'static,
+    {
+        self.signal.watch(callback)
+    }
+
+    /// Get the signal's unique ID.
+    pub fn id(&self) -> usize {
+        self.signal.id()
+    }
+
+    /// Get the full signal behind this handle.
+    pub fn into_signal(self) -> Signal<T> {
+        self.signal
+    }
+}
+
+impl<T: Clone + Send + Sync + 'static> WriteSignal<T> {
+    /// Set a new value for the signal.
+    pub fn set(&self, new_value: T) {
+        self.signal.set(new_value);
+    }
+
+    /// Update the value using a function.
+    pub fn update(&self, f: impl FnOnce(&mut T)) {
+        self.signal.update(f);
+    }
+
+    /// Get the signal's unique ID.
+    pub fn id(&self) -> usize {
+        self.signal.id()
+    }
+
+    /// Get the full signal behind this handle.
+    pub fn into_signal(self) -> Signal<T> {
+        self.signal
+    }
+}
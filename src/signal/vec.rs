@@ -0,0 +1,47 @@
+use super::Signal;
+
+/// Mutation helpers for vector signals.
+///
+/// Each method mutates the vector under the write lock and then notifies
+/// observers, like [`Signal::update`], even if the vector is unchanged.
+pub trait VecSignalExt<T> {
+    /// Append an item.
+    fn push(&self, item: T);
+
+    /// Remove and return the last item.
+    fn pop(&self) -> Option<T>;
+
+    /// Remove and return the item at `index`, shifting later items down.
+    ///
+    /// Unlike `Vec::remove` this returns `None` instead of panicking when
+    /// `index` is out of bounds.
+    fn remove(&self, index: usize) -> Option<T>;
+
+    /// Remove every item.
+    fn clear(&self);
+
+    /// Keep only the items `keep` accepts.
+    fn retain(&self, keep: impl FnMut(&T) -> bool);
+}
+
+impl<T: Clone + Send + Sync + 'static> VecSignalExt<T> for Signal<Vec<T>> {
+    fn push(&self, item: T) {
+        self.update(|items| items.push(item));
+    }
+
+    fn pop(&self) -> Option<T> {
+        self.update_returning(Vec::pop)
+    }
+
+    fn remove(&self, index: usize) -> Option<T> {
+        self.update_returning(|items| (index < items.len()).then(|| items.remove(index)))
+    }
+
+    fn clear(&self) {
+        self.update(Vec::clear);
+    }
+
+    fn retain(&self, keep: impl FnMut(&T) -> bool) {
+        self.update(|items| items.retain(keep));
+    }
+}
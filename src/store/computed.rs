@@ -0,0 +1,42 @@
+use super::Store;
+use crate::memo::Memo;
+
+/// A fixed set of stores that can be read together as one tuple of states.
+pub trait StoreSet {
+    /// The tuple of cloned states.
+    type State;
+
+    /// Read every store, tracking each as a dependency of the current observer.
+    fn get(&self) -> Self::State;
+}
+
+macro_rules! impl_store_set {
+    ($($store:ident: $state:ident),+) => {
+        impl<$($state: Clone + 'static),+> StoreSet for ($(Store<$state>,)+) {
+            type State = ($($state,)+);
+
+            fn get(&self) -> Self::State {
+                let ($($store,)+) = self;
+                ($($store.get(),)+)
+            }
+        }
+    };
+}
+
+impl_store_set!(a: A);
+impl_store_set!(a: A, b: B);
+impl_store_set!(a: A, b: B, c: C);
+impl_store_set!(a: A, b: B, c: C, d: D);
+
+/// Create a memo computed from the states of several stores.
+///
+/// The memo is cached between reads and recomputes after any of the stores
+/// changes.
+pub fn computed<S, T, F>(stores: S, f: F) -> Memo<T>
+where
+    S: StoreSet + Send + Sync + 'static,
+    T: Clone + 'static,
+    F: Fn(S::State) -> T + Send + Sync + 'static,
+{
+    Memo::new(move || f(stores.get()))
+}
@@ -0,0 +1,79 @@
+use super::Store;
+use std::sync::{Arc, Mutex};
+
+type Reducer<S, A> = Arc<dyn Fn(&mut S, &A) + Send + Sync>;
+
+/// A store whose state only changes by dispatching actions, which are logged.
+///
+/// Unlike [`Store::time_travel`], which records states, this records the
+/// actions themselves, so the log can be replayed onto any initial state.
+pub struct EventSourcedStore<S, A> {
+    store: Store<S>,
+    reducer: Reducer<S, A>,
+    log: Arc<Mutex<Vec<A>>>,
+}
+
+impl<S: Clone + 'static, A: Clone> EventSourcedStore<S, A> {
+    /// Create a store that applies dispatched actions with `reducer`.
+    pub fn new<F>(initial: S, reducer: F) -> Self
+    where
+        F: Fn(&mut S, &A) + Send + Sync + 'static,
+    {
+        Self {
+            store: Store::new(initial),
+            reducer: Arc::new(reducer),
+            log: Arc::new(Mutex::new(Vec::new())),
+        }
+    }
+
+    /// Apply an action to the state, log it and notify subscribers.
+    ///
+    /// An action vetoed by a middleware on the underlying store, or
+    /// dispatched to a frozen one, is neither applied nor logged.
+    pub fn dispatch(&self, action: A) {
+        // Hold the log across the commit so the log order matches the apply
+        // order, and only log what was committed
+        let mut log = self.log.lock().unwrap();
+        let committed = self.store.commit(|state| {
+            (self.reducer)(state, &action);
+            Some(())
+        });
+        if committed.is_some() {
+            log.push(action);
+            drop(log);
+            self.store.notify();
+        }
+    }
+
+    /// Get the dispatched actions, oldest first.
+    pub fn actions(&self) -> Vec<A> {
+        self.log.lock().unwrap().clone()
+    }
+
+    /// Apply every logged action to `initial` and return the result.
+    ///
+    /// Neither this store nor its subscribers are affected.
+    pub fn replay(&self, mut initial: S) -> S {
+        for action in self.actions() {
+            (self.reducer)(&mut initial, &action);
+        }
+        initial
+    }
+
+    /// Get the underlying store, for reading and subscribing.
+    ///
+    /// Writing to it directly bypasses the action log.
+    pub fn store(&self) -> &Store<S> {
+        &self.store
+    }
+}
+
+impl<S: Clone, A> Clone for EventSourcedStore<S, A> {
+    fn clone(&self) -> Self {
+        Self {
+            store: self.store.clone(),
+            reducer: Arc::clone(&self.reducer),
+            log: Arc::clone(&self.log),
+        }
+    }
+}
@@ -0,0 +1,80 @@
+use super::Store;
+
+/// An item that can be identified by a stable key inside a list store.
+pub trait Keyed {
+    /// The item's identifier.
+    type Key: Clone + PartialEq;
+
+    /// Get the item's key.
+    fn key(&self) -> Self::Key;
+}
+
+/// A keyed list operation applied by one of the `Store<Vec<T>>` helpers.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ListChange<K> {
+    /// A new item was inserted at `index`.
+    Inserted { key: K, index: usize },
+    /// An item with the same key already existed and was replaced in place.
+    Replaced { key: K, index: usize },
+    /// The item was removed from `index`.
+    Removed { key: K, index: usize },
+    /// The item was moved from one index to another.
+    Moved { key: K, from: usize, to: usize },
+}
+
+impl<T: Keyed + Clone + 'static> Store<Vec<T>> {
+    /// Insert an item at `index`, or replace the item with the same key.
+    ///
+    /// The index is clamped to the length of the list. An existing item keeps
+    /// its position when replaced. Subscribers are notified once.
+    pub fn insert_item(&self, index: usize, item: T) -> ListChange<T::Key> {
+        self.try_update(|items| {
+            let key = item.key();
+            Some(
+                match items.iter().position(|existing| existing.key() == key) {
+                    Some(index) => {
+                        items[index] = item;
+                        ListChange::Replaced { key, index }
+                    }
+                    None => {
+                        let index = index.min(items.len());
+                        items.insert(index, item);
+                        ListChange::Inserted { key, index }
+                    }
+                },
+            )
+        })
+        .expect("insert always applies")
+    }
+
+    /// Remove the item with the given key.
+    ///
+    /// Returns `None` without notifying if no item has that key.
+    pub fn remove_item(&self, key: &T::Key) -> Option<ListChange<T::Key>> {
+        self.try_update(|items| {
+            let index = items.iter().position(|item| item.key() == *key)?;
+            items.remove(index);
+            Some(ListChange::Removed {
+                key: key.clone(),
+                index,
+            })
+        })
+    }
+
+    /// Move the item with the given key to `index`, clamped to the list.
+    ///
+    /// Returns `None` without notifying if no item has that key.
+    pub fn move_item(&self, key: &T::Key, index: usize) -> Option<ListChange<T::Key>> {
+        self.try_update(|items| {
+            let from = items.iter().position(|item| item.key() == *key)?;
+            let to = index.min(items.len() - 1);
+            let item = items.remove(from);
+            items.insert(to, item);
+            Some(ListChange::Moved {
+                key: key.clone(),
+                from,
+                to,
+            })
+        })
+    }
+}
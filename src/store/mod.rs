@@ -1,3 +1,12 @@
+mod computed;
+mod event_sourced;
+mod list;
+mod reducer;
+#[allow(clippy::module_inception)]
 mod store;
 
-pub use store::Store;
+pub use computed::{computed, StoreSet};
+pub use event_sourced::EventSourcedStore;
+pub use list::{Keyed, ListChange};
+pub use reducer::ReducerStore;
+pub use store::{Store, StoreSubscription, SubscriberResponse, VersionedUpdateError};
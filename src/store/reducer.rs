@@ -0,0 +1,95 @@
+use super::Store;
+use std::ops::Deref;
+use std::sync::{Arc, RwLock};
+
+type Reducer<T, A> = Arc<dyn Fn(&T, &A) -> T + Send + Sync>;
+type ActionSubscriber<T, A> = Arc<dyn Fn(&T, &A) + Send + Sync>;
+
+/// A store that changes state by dispatching actions to a reducer.
+///
+/// Created with [`Store::with_reducer`]. It derefs to the underlying
+/// [`Store`], so reading, subscribing and direct `update`/`set` calls keep
+/// working alongside [`dispatch`](Self::dispatch).
+pub struct ReducerStore<T, A> {
+    store: Store<T>,
+    reducer: Reducer<T, A>,
+    action_subscribers: Arc<RwLock<Vec<ActionSubscriber<T, A>>>>,
+}
+
+impl<T: Clone + 'static> Store<T> {
+    /// Create a store whose state transitions are computed by `reducer`.
+    ///
+    /// `reducer` produces the next state from the current one and an action,
+    /// like a Redux reducer.
+    pub fn with_reducer<A, F>(initial: T, reducer: F) -> ReducerStore<T, A>
+    where
+        F: Fn(&T, &A) -> T + Send + Sync + 'static,
+    {
+        ReducerStore {
+            store: Store::new(initial),
+            reducer: Arc::new(reducer),
+            action_subscribers: Arc::new(RwLock::new(Vec::new())),
+        }
+    }
+}
+
+impl<T: Clone + 'static, A: 'static> ReducerStore<T, A> {
+    /// Replace the state with the reducer's result for `action` and notify
+    /// subscribers.
+    ///
+    /// Action subscribers run after the plain ones, untracked, and not at
+    /// all if a middleware vetoes the change. Inside a batch they run when
+    /// it ends, once per dispatched action, each with the state that action
+    /// produced.
+    pub fn dispatch(&self, action: A) {
+        let subscribers = self.action_subscribers.read().unwrap().clone();
+        let reduced = self.store.try_update(|state| {
+            *state = (self.reducer)(state, &action);
+            Some((!subscribers.is_empty()).then(|| state.clone()))
+        });
+        if let Some(Some(state)) = reduced {
+            self.store.deliver_after_subscribers(move || {
+                for subscriber in &subscribers {
+                    subscriber(&state, &action);
+                }
+            });
+        }
+    }
+
+    /// Subscribe to dispatched actions, paired with the state they produced.
+    ///
+    /// Changes made through the underlying store's `update` or `set` are
+    /// not reported here.
+    pub fn subscribe_with_action<F>(&self, callback: F)
+    where
+        F: Fn(&T, &A) + Send + Sync + 'static,
+    {
+        self.action_subscribers
+            .write()
+            .unwrap()
+            .push(Arc::new(callback));
+    }
+
+    /// Get the underlying store.
+    pub fn store(&self) -> &Store<T> {
+        &self.store
+    }
+}
+
+impl<T, A> Deref for ReducerStore<T, A> {
+    type Target = Store<T>;
+
+    fn deref(&self) -> &Store<T> {
+        &self.store
+    }
+}
+
+impl<T: Clone, A> Clone for ReducerStore<T, A> {
+    fn clone(&self) -> Self {
+        Self {
+            store: self.store.clone(),
+            reducer: Arc::clone(&self.reducer),
+            action_subscribers: Arc::clone(&self.action_subscribers),
+        }
+    }
+}
@@ -1,43 +1,531 @@
-use std::sync::{Arc, RwLock};
+use crate::runtime::ReactiveRuntime;
+use crate::signal::Signal;
+use std::collections::VecDeque;
+use std::fmt;
+use std::ops::Deref;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, RwLock, Weak};
 
-type Subscriber<T> = Box<dyn Fn(&T) + Send + Sync>;
+type Subscriber<T> = Arc<dyn Fn(&T) -> SubscriberResponse + Send + Sync>;
+type FrameCallback<T> = Arc<dyn Fn(&T) + Send + Sync>;
+type Middleware<T> = Arc<dyn Fn(&T, &mut T) -> bool + Send + Sync>;
+
+/// What a subscriber asks of the writer after seeing a change.
+///
+/// Responses from all subscribers are combined and returned by
+/// [`Store::update`] and [`Store::set`], so the writer can batch expensive
+/// follow-up work such as a repaint.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum SubscriberResponse {
+    /// Nothing further is needed.
+    #[default]
+    Done,
+    /// The subscriber needs the caller to repaint.
+    NeedsRepaint,
+}
+
+impl SubscriberResponse {
+    /// Whether the response asks for a repaint.
+    pub fn needs_repaint(self) -> bool {
+        self == SubscriberResponse::NeedsRepaint
+    }
+
+    /// Combine two responses, keeping the stronger request.
+    fn merge(self, other: Self) -> Self {
+        if self.needs_repaint() {
+            self
+        } else {
+            other
+        }
+    }
+}
+
+/// Adapt a plain callback to the subscriber signature.
+fn respond_done<T>(callback: impl Fn(&T) + Send + Sync + 'static) -> Subscriber<T> {
+    Arc::new(move |state| {
+        callback(state);
+        SubscriberResponse::Done
+    })
+}
+
+/// A registered subscriber, optionally identified by a user-supplied key.
+struct SubscriberEntry<T> {
+    // Identifies the entry for `StoreSubscription`
+    id: u64,
+    key: Option<String>,
+    // Removed once this no longer upgrades, see `Store::subscribe_while`
+    alive: Option<Weak<()>>,
+    callback: Subscriber<T>,
+}
+
+/// Subscribers that fire at most once per frame, see [`Store::flush_frame`].
+struct FrameSubscribers<T> {
+    callbacks: RwLock<Vec<FrameCallback<T>>>,
+    // Set when the state changed since the last flush
+    dirty: AtomicBool,
+}
+
+/// Revision bookkeeping, plus recorded states for time-travel stores.
+struct Timeline<T> {
+    // Revision of the current state
+    current: u64,
+    // Highest revision ever committed
+    latest: u64,
+    // Recorded states, oldest first, when recording is enabled
+    entries: Option<VecDeque<(u64, T)>>,
+    capacity: Option<usize>,
+}
+
+impl<T: Clone> Timeline<T> {
+    fn new(initial: Option<&T>, capacity: Option<usize>) -> Self {
+        Self {
+            current: 0,
+            latest: 0,
+            entries: initial.map(|state| VecDeque::from([(0, state.clone())])),
+            capacity,
+        }
+    }
+
+    /// Assign a new revision to `state`, discarding any undone revisions.
+    fn commit(&mut self, state: &T) {
+        self.latest += 1;
+        let previous = std::mem::replace(&mut self.current, self.latest);
+        if let Some(entries) = self.entries.as_mut() {
+            if let Some(index) = entries.iter().position(|(rev, _)| *rev == previous) {
+                entries.truncate(index + 1);
+            }
+            entries.push_back((self.current, state.clone()));
+            if let Some(capacity) = self.capacity {
+                while entries.len() > capacity.max(1) {
+                    entries.pop_front();
+                }
+            }
+        }
+    }
+
+    /// Get the recorded revision `offset` entries away from the current one.
+    fn neighbor(&self, offset: isize) -> Option<u64> {
+        let entries = self.entries.as_ref()?;
+        let index = entries.iter().position(|(rev, _)| *rev == self.current)?;
+        let (rev, _) = entries.get(index.checked_add_signed(offset)?)?;
+        Some(*rev)
+    }
+}
+
+/// Why [`Store::try_update_versioned`] did not apply an update.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum VersionedUpdateError {
+    /// The state was not at the expected revision; retrying from `actual`
+    /// may succeed.
+    Conflict {
+        /// The revision the caller expected.
+        expected: u64,
+        /// The revision the store was actually at.
+        actual: u64,
+    },
+    /// The store is [frozen](Store::freeze), so no retry can succeed.
+    Frozen,
+    /// A middleware rejected the change.
+    Vetoed,
+}
+
+impl fmt::Display for VersionedUpdateError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Conflict { expected, actual } => write!(
+                f,
+                "store is at revision {actual}, expected revision {expected}"
+            ),
+            Self::Frozen => write!(f, "store is frozen"),
+            Self::Vetoed => write!(f, "update was vetoed by a middleware"),
+        }
+    }
+}
+
+impl std::error::Error for VersionedUpdateError {}
+
+// Subscriber IDs only need to be unique within a store, a global counter is simplest
+static NEXT_SUBSCRIBER: AtomicU64 = AtomicU64::new(0);
+
+/// Guard for a store subscription; dropping it unsubscribes.
+#[must_use = "dropping the subscription unsubscribes immediately"]
+pub struct StoreSubscription {
+    remove: Option<Box<dyn FnOnce() + Send + Sync>>,
+}
+
+impl Drop for StoreSubscription {
+    fn drop(&mut self) {
+        if let Some(remove) = self.remove.take() {
+            remove();
+        }
+    }
+}
 
 /// A thread-safe store for managing application state.
 pub struct Store<T> {
     state: Arc<RwLock<T>>,
-    subscribers: Arc<RwLock<Vec<Subscriber<T>>>>,
+    subscribers: Arc<RwLock<Vec<SubscriberEntry<T>>>>,
+    timeline: Arc<Mutex<Timeline<T>>>,
+    frame: Arc<FrameSubscribers<T>>,
+    frozen: Arc<AtomicBool>,
+    middleware: Arc<RwLock<Vec<Middleware<T>>>>,
+    id: usize,
+    runtime: Arc<ReactiveRuntime>,
 }
 
-impl<T: Clone> Store<T> {
+impl<T: Clone + 'static> Store<T> {
     /// Create a new store with the given initial state.
     pub fn new(initial: T) -> Self {
+        Self::with_timeline(initial, false, None)
+    }
+
+    /// Create a store registered with the runtime under a display name.
+    ///
+    /// The name shows up in [`ReactiveRuntime::dump_graph`] and
+    /// [`ReactiveRuntime::name_of`], next to the store's [`id`](Self::id).
+    pub fn new_named(initial: T, name: impl Into<String>) -> Self {
+        let store = Self::new(initial);
+        store.runtime.set_name(store.id, name.into());
+        store
+    }
+
+    /// Create a store that records every committed state for time travel.
+    ///
+    /// Up to `capacity` states are kept, dropping the oldest first; `None`
+    /// keeps all of them. See [`history`](Self::history) and
+    /// [`goto`](Self::goto).
+    pub fn time_travel(initial: T, capacity: Option<usize>) -> Self {
+        Self::with_timeline(initial, true, capacity)
+    }
+
+    /// Create a store with undo and redo of up to `capacity` changes.
+    ///
+    /// Every `update` or `set` records the new state; once more than
+    /// `capacity` changes are recorded the oldest are evicted. A change made
+    /// after an [`undo`](Self::undo) discards the undone states.
+    pub fn with_history(initial: T, capacity: usize) -> Self {
+        Self::time_travel(initial, Some(capacity.saturating_add(1)))
+    }
+
+    fn with_timeline(initial: T, record: bool, capacity: Option<usize>) -> Self {
+        let runtime = ReactiveRuntime::current();
+        let timeline = Timeline::new(record.then_some(&initial), capacity);
         Self {
             state: Arc::new(RwLock::new(initial)),
             subscribers: Arc::new(RwLock::new(Vec::new())),
+            timeline: Arc::new(Mutex::new(timeline)),
+            frame: Arc::new(FrameSubscribers {
+                callbacks: RwLock::new(Vec::new()),
+                dirty: AtomicBool::new(false),
+            }),
+            frozen: Arc::new(AtomicBool::new(false)),
+            middleware: Arc::new(RwLock::new(Vec::new())),
+            id: runtime.next_id(),
+            runtime,
         }
     }
 
     /// Get a clone of the current state.
+    ///
+    /// Inside an effect or memo this tracks the store as a dependency.
     pub fn get(&self) -> T {
+        self.runtime.track_read(self.id);
         self.state.read().unwrap().clone()
     }
 
+    /// Borrow the current state without cloning it.
+    ///
+    /// Like [`get`](Self::get) this tracks the store as a dependency, but the
+    /// returned guard holds the store's read lock until it is dropped.
+    /// Writing to the store while holding it, directly or through a
+    /// subscriber or effect, deadlocks; keep the guard short-lived.
+    pub fn get_cow(&self) -> impl Deref<Target = T> + '_ {
+        self.runtime.track_read(self.id);
+        self.state.read().unwrap()
+    }
+
+    /// Create a signal of a projection of the state.
+    ///
+    /// The signal is recomputed on every state change but only written and
+    /// notified when the selected value differs, so its dependents ignore
+    /// changes to unrelated parts of the state. It keeps the store alive.
+    pub fn select<U, F>(&self, f: F) -> Signal<U>
+    where
+        T: Send + Sync + 'static,
+        U: Clone + PartialEq + Send + Sync + 'static,
+        F: Fn(&T) -> U + Send + Sync + 'static,
+    {
+        let store = self.clone();
+        Signal::computed_deduped(move || f(&store.get_cow()))
+    }
+
+    /// Get the store's unique ID in the reactive runtime.
+    pub fn id(&self) -> usize {
+        self.id
+    }
+
+    /// Get the revision of the current state.
+    ///
+    /// The initial state is revision 0 and every `update` or `set` commits a
+    /// new, higher revision.
+    pub fn revision(&self) -> u64 {
+        self.timeline.lock().unwrap().current
+    }
+
+    /// Get the recorded states as `(revision, state)` pairs, oldest first.
+    ///
+    /// Empty unless the store was created with [`time_travel`](Self::time_travel).
+    pub fn history(&self) -> Vec<(u64, T)> {
+        let timeline = self.timeline.lock().unwrap();
+        timeline
+            .entries
+            .as_ref()
+            .map(|entries| entries.iter().cloned().collect())
+            .unwrap_or_default()
+    }
+
+    /// Restore the state recorded at `revision` and notify subscribers.
+    ///
+    /// Later revisions stay available to `goto` until the next edit, which
+    /// branches from the restored state and discards them. Returns `false`
+    /// if the revision is not recorded or the store is frozen.
+    pub fn goto(&self, revision: u64) -> bool {
+        self.travel(|_| Some(revision))
+    }
+
+    /// Step back to the previous recorded state and notify subscribers.
+    ///
+    /// Returns `false` if there is nothing to undo. Only stores created with
+    /// [`with_history`](Self::with_history) or
+    /// [`time_travel`](Self::time_travel) record states.
+    pub fn undo(&self) -> bool {
+        self.travel(|timeline| timeline.neighbor(-1))
+    }
+
+    /// Step forward to the state the last [`undo`](Self::undo) left.
+    ///
+    /// Returns `false` if there is nothing to redo.
+    pub fn redo(&self) -> bool {
+        self.travel(|timeline| timeline.neighbor(1))
+    }
+
+    /// Check whether [`undo`](Self::undo) would change the state.
+    pub fn can_undo(&self) -> bool {
+        self.timeline.lock().unwrap().neighbor(-1).is_some()
+    }
+
+    /// Check whether [`redo`](Self::redo) would change the state.
+    pub fn can_redo(&self) -> bool {
+        self.timeline.lock().unwrap().neighbor(1).is_some()
+    }
+
+    /// Restore the recorded state at the revision `pick` chooses.
+    fn travel(&self, pick: impl FnOnce(&Timeline<T>) -> Option<u64>) -> bool {
+        if self.is_frozen() {
+            return false;
+        }
+        let restored = {
+            // Same lock order as `commit`, held until the state is assigned so
+            // no commit can land between picking a revision and restoring it
+            let _gate = self.runtime.write_gate();
+            let mut state = self.state.write().unwrap();
+            let mut timeline = self.timeline.lock().unwrap();
+            let Some(revision) = pick(&timeline) else {
+                return false;
+            };
+            let recorded = timeline.entries.as_ref().and_then(|entries| {
+                entries
+                    .iter()
+                    .find(|(rev, _)| *rev == revision)
+                    .map(|(_, state)| state.clone())
+            });
+            match recorded {
+                Some(recorded) => {
+                    timeline.current = revision;
+                    *state = recorded;
+                    true
+                }
+                None => false,
+            }
+        };
+        if restored {
+            self.notify();
+        }
+        restored
+    }
+
+    /// Reject every further change to the state, for example on shutdown.
+    ///
+    /// Afterwards `update`, `set` and `goto` are no-ops that notify nobody,
+    /// shared by every clone of this store. Reading and subscribing keep
+    /// working. There is no way to unfreeze.
+    pub fn freeze(&self) {
+        self.frozen.store(true, Ordering::SeqCst);
+    }
+
+    /// Check whether the store has been frozen.
+    pub fn is_frozen(&self) -> bool {
+        self.frozen.load(Ordering::SeqCst)
+    }
+
     /// Update the state using a function.
-    pub fn update<F>(&self, f: F)
+    ///
+    /// Returns the combined [`SubscriberResponse`] of all subscribers, or
+    /// [`SubscriberResponse::Done`] without calling `f` if the store is
+    /// [frozen](Self::freeze). Inside a batch, subscribers run when the batch
+    /// ends and this returns [`SubscriberResponse::Done`], as with
+    /// [`notify`](Self::notify).
+    pub fn update<F>(&self, f: F) -> SubscriberResponse
     where
         F: FnOnce(&mut T),
     {
-        {
-            let mut state = self.state.write().unwrap();
-            f(&mut *state);
+        let committed = self.commit(|state| {
+            f(state);
+            Some(())
+        });
+        match committed {
+            Some(()) => self.notify(),
+            None => SubscriberResponse::Done,
         }
+    }
+
+    /// Update the state only if `f` returns `Some`, notifying only then.
+    ///
+    /// `f` must leave the state untouched when it returns `None`.
+    pub(crate) fn try_update<R>(&self, f: impl FnOnce(&mut T) -> Option<R>) -> Option<R> {
+        let result = self.commit(f)?;
         self.notify();
+        Some(result)
+    }
+
+    /// Update the state only if it is still at revision `expected`.
+    ///
+    /// For optimistic concurrency: read [`revision`](Self::revision), prepare
+    /// the change, then apply it here and retry on
+    /// [`Conflict`](VersionedUpdateError::Conflict). Returns the new
+    /// revision, after notifying subscribers.
+    pub fn try_update_versioned<F>(&self, expected: u64, f: F) -> Result<u64, VersionedUpdateError>
+    where
+        F: FnOnce(&mut T),
+    {
+        // `commit` only skips the closure when the store is frozen
+        let mut error = VersionedUpdateError::Frozen;
+        let committed = self.commit(|state| {
+            let timeline = self.timeline.lock().unwrap();
+            if timeline.current != expected {
+                error = VersionedUpdateError::Conflict {
+                    expected,
+                    actual: timeline.current,
+                };
+                return None;
+            }
+            // Past this point only a middleware can stop the commit
+            error = VersionedUpdateError::Vetoed;
+            // Only commits change `latest`, and they are serialized by the
+            // state lock held here
+            let revision = timeline.latest + 1;
+            drop(timeline);
+            f(state);
+            Some(revision)
+        });
+        match committed {
+            Some(revision) => {
+                self.notify();
+                Ok(revision)
+            }
+            None => Err(error),
+        }
+    }
+
+    /// Apply `f` and commit a new revision without notifying anyone.
+    ///
+    /// Returns `None` without committing if the store is frozen, `f` returns
+    /// `None` or a middleware vetoes the change.
+    pub(crate) fn commit<R>(&self, f: impl FnOnce(&mut T) -> Option<R>) -> Option<R> {
+        if self.is_frozen() {
+            return None;
+        }
+        let _gate = self.runtime.write_gate();
+        let mut state = self.state.write().unwrap();
+        let middleware = self.middleware.read().unwrap();
+        let result = if middleware.is_empty() {
+            f(&mut *state)?
+        } else {
+            // Work on a copy so a veto leaves the state untouched
+            let mut next = state.clone();
+            let result = f(&mut next)?;
+            if !middleware
+                .iter()
+                .all(|middleware| middleware(&state, &mut next))
+            {
+                return None;
+            }
+            *state = next;
+            result
+        };
+        self.timeline.lock().unwrap().commit(&state);
+        Some(result)
     }
 
     /// Set a new state value.
-    pub fn set(&self, new_state: T) {
-        *self.state.write().unwrap() = new_state;
-        self.notify();
+    ///
+    /// Returns the combined [`SubscriberResponse`] of all subscribers. Does
+    /// nothing if the store is [frozen](Self::freeze).
+    pub fn set(&self, new_state: T) -> SubscriberResponse {
+        self.update(|state| *state = new_state)
+    }
+
+    /// Add a middleware that runs on every `update` and `set`.
+    ///
+    /// Each middleware receives the current state and the proposed next
+    /// state, which it may modify. Returning `false` vetoes the change: the
+    /// state stays untouched and nobody is notified. Middlewares run in
+    /// registration order and stop at the first veto. `goto`, `undo` and
+    /// `redo` restore recorded states without running them.
+    ///
+    /// While any middleware is registered every write clones the state to
+    /// propose it. Middlewares run under the store's write lock and must not
+    /// access the store.
+    pub fn add_middleware<F>(&self, middleware: F)
+    where
+        F: Fn(&T, &mut T) -> bool + Send + Sync + 'static,
+    {
+        self.middleware.write().unwrap().push(Arc::new(middleware));
+    }
+
+    /// Update the state in place, notifying only if it changed.
+    ///
+    /// Returns whether the state changed. The state is cloned before `f` runs
+    /// to compare against, and an unchanged state commits no new revision.
+    pub fn update_if_changed<F>(&self, f: F) -> bool
+    where
+        T: PartialEq,
+        F: FnOnce(&mut T),
+    {
+        self.try_update(|state| {
+            let before = state.clone();
+            f(state);
+            (*state != before).then_some(())
+        })
+        .is_some()
+    }
+
+    /// Set a new state only if it differs from the current one.
+    ///
+    /// Returns whether the state changed; an equal state is neither
+    /// committed nor notified.
+    pub fn set_if_changed(&self, new_state: T) -> bool
+    where
+        T: PartialEq,
+    {
+        self.try_update(|state| {
+            if *state == new_state {
+                return None;
+            }
+            *state = new_state;
+            Some(())
+        })
+        .is_some()
     }
 
     /// Subscribe to state changes.
@@ -45,16 +533,269 @@ impl<T: Clone> Store<T> {
     where
         F: Fn(&T) + Send + Sync + 'static,
     {
-        self.subscribers.write().unwrap().push(Box::new(callback));
+        self.push_subscriber(None, respond_done(callback));
+    }
+
+    /// Subscribe to state changes until the returned guard is dropped.
+    pub fn subscribe_scoped<F>(&self, callback: F) -> StoreSubscription
+    where
+        T: Send + Sync + 'static,
+        F: Fn(&T) + Send + Sync + 'static,
+    {
+        let id = self.push_subscriber(None, respond_done(callback));
+        self.subscription(id)
+    }
+
+    /// Subscribe with a callback that tells the writer what it needs.
+    ///
+    /// `update` and `set` return the combined responses of all subscribers;
+    /// plain [`subscribe`](Self::subscribe) callbacks respond with
+    /// [`SubscriberResponse::Done`].
+    pub fn subscribe_responding<F>(&self, callback: F)
+    where
+        F: Fn(&T) -> SubscriberResponse + Send + Sync + 'static,
+    {
+        self.push_subscriber(None, Arc::new(callback));
+    }
+
+    /// Subscribe to states that pass `predicate`, until the guard is dropped.
+    ///
+    /// `predicate` is checked against each new state and `callback` only runs
+    /// when it returns `true`.
+    pub fn subscribe_filtered<P, F>(&self, predicate: P, callback: F) -> StoreSubscription
+    where
+        T: Send + Sync + 'static,
+        P: Fn(&T) -> bool + Send + Sync + 'static,
+        F: Fn(&T) + Send + Sync + 'static,
+    {
+        let id = self.push_subscriber(
+            None,
+            respond_done(move |state| {
+                if predicate(state) {
+                    callback(state);
+                }
+            }),
+        );
+        self.subscription(id)
+    }
+
+    fn push_subscriber(&self, alive: Option<Weak<()>>, callback: Subscriber<T>) -> u64 {
+        let id = NEXT_SUBSCRIBER.fetch_add(1, Ordering::Relaxed);
+        self.subscribers.write().unwrap().push(SubscriberEntry {
+            id,
+            key: None,
+            alive,
+            callback,
+        });
+        id
+    }
+
+    /// Create a guard removing the subscriber `id` from this store when dropped.
+    fn subscription(&self, id: u64) -> StoreSubscription
+    where
+        T: Send + Sync + 'static,
+    {
+        let subscribers = Arc::downgrade(&self.subscribers);
+        StoreSubscription {
+            remove: Some(Box::new(move || {
+                let Some(subscribers) = subscribers.upgrade() else {
+                    return;
+                };
+                let mut subscribers = subscribers.write().unwrap();
+                let removed = subscribers
+                    .iter()
+                    .position(|entry| entry.id == id)
+                    .map(|index| subscribers.remove(index));
+                // Drop the callback outside the lock, it may own other primitives
+                drop(subscribers);
+                drop(removed);
+            })),
+        }
+    }
+
+    /// Subscribe to state changes for as long as `alive` can be upgraded.
+    ///
+    /// Ties the subscription to the lifetime of an external `Arc<()>` token
+    /// instead of a guard: once the token is dropped, the next notification
+    /// removes the subscriber without calling it.
+    pub fn subscribe_while<F>(&self, alive: Weak<()>, callback: F)
+    where
+        F: Fn(&T) + Send + Sync + 'static,
+    {
+        self.push_subscriber(Some(alive), respond_done(callback));
+    }
+
+    /// Subscribe to state changes under a key.
+    ///
+    /// Subscribing again with the same key replaces the previous callback
+    /// instead of adding a second one.
+    pub fn subscribe_unique<F>(&self, key: impl Into<String>, callback: F)
+    where
+        F: Fn(&T) + Send + Sync + 'static,
+    {
+        let key = key.into();
+        let mut subscribers = self.subscribers.write().unwrap();
+        let callback = respond_done(callback);
+        let replaced = match subscribers
+            .iter_mut()
+            .find(|entry| entry.key.as_deref() == Some(key.as_str()))
+        {
+            Some(entry) => Some(std::mem::replace(&mut entry.callback, callback)),
+            None => {
+                subscribers.push(SubscriberEntry {
+                    id: NEXT_SUBSCRIBER.fetch_add(1, Ordering::Relaxed),
+                    key: Some(key),
+                    alive: None,
+                    callback,
+                });
+                None
+            }
+        };
+        // Drop the old callback outside the lock, it may own other primitives
+        drop(subscribers);
+        drop(replaced);
+    }
+
+    /// Subscribe to state changes at most once per frame.
+    ///
+    /// Changes only mark the frame dirty. The next [`flush_frame`](Self::flush_frame)
+    /// calls the callback once with the latest state, however many updates
+    /// happened since the previous flush.
+    pub fn subscribe_coalesced<F>(&self, callback: F)
+    where
+        F: Fn(&T) + Send + Sync + 'static,
+    {
+        self.frame
+            .callbacks
+            .write()
+            .unwrap()
+            .push(Arc::new(callback));
+    }
+
+    /// Deliver the latest state to coalesced subscribers if it changed.
+    ///
+    /// Call this once per frame, for example from a render loop or a timer.
+    /// Returns whether the subscribers were called.
+    pub fn flush_frame(&self) -> bool {
+        if !self.frame.dirty.swap(false, Ordering::SeqCst) {
+            return false;
+        }
+        let state = self.state.read().unwrap().clone();
+        let callbacks = self.frame.callbacks.read().unwrap().clone();
+        self.runtime.untracked(|| {
+            for callback in callbacks {
+                callback(&state);
+            }
+        });
+        true
+    }
+
+    /// Subscribe to state changes with a callback that can mutate its own state.
+    ///
+    /// The callback is kept behind a `Mutex`. It may read and write this
+    /// store, but a write that notifies subscribers outside a batch calls it
+    /// again while it is still running, which deadlocks on that `Mutex`.
+    pub fn subscribe_mut<F>(&self, callback: F)
+    where
+        F: FnMut(&T) + Send + 'static,
+    {
+        let callback = Mutex::new(callback);
+        self.subscribe(move |state| (callback.lock().unwrap())(state));
     }
 
     /// Notify all subscribers of a state change.
-    fn notify(&self) {
-        let state = self.state.read().unwrap();
-        let subscribers = self.subscribers.read().unwrap();
-        for subscriber in subscribers.iter() {
-            subscriber(&*state);
+    ///
+    /// Inside a [`batch`](crate::batch), subscribers run once when the
+    /// outermost batch finishes, seeing the state at that point, and this
+    /// returns [`SubscriberResponse::Done`]. Otherwise they run immediately.
+    /// Effects and memos tracking the store are notified through the
+    /// runtime, so they are batched as well.
+    ///
+    /// `update` and `set` call this for you. Call it directly only after
+    /// mutating state the store cannot see, such as through interior
+    /// mutability inside `T`. Such changes do not commit a revision, so they
+    /// are missing from [`history`](Self::history), and nothing is notified
+    /// if you forget to call this.
+    ///
+    /// Returns the combined [`SubscriberResponse`] of all subscribers.
+    pub fn notify(&self) -> SubscriberResponse {
+        let store = self.clone();
+        let deferred = self.runtime.defer_delivery(Some(self.id), move || {
+            store.deliver();
+        });
+        let response = match deferred {
+            Some(_) => self.deliver(),
+            None => SubscriberResponse::Done,
+        };
+        self.frame.dirty.store(true, Ordering::SeqCst);
+        self.runtime.notify_observers(self.id);
+        response
+    }
+
+    /// Run `deliver` untracked after this store's subscribers have seen the
+    /// latest change: now, or when the current batch ends.
+    pub(crate) fn deliver_after_subscribers(&self, deliver: impl FnOnce() + 'static) {
+        let runtime = Arc::clone(&self.runtime);
+        let deliver = move || runtime.untracked(deliver);
+        // Queued behind the subscribers' own delivery, if batching
+        if let Some(deliver) = self.runtime.defer_delivery(None, deliver) {
+            deliver();
+        }
+    }
+
+    /// Run every subscriber with the current state.
+    fn deliver(&self) -> SubscriberResponse {
+        // Snapshot under the locks, then run subscribers with none held so
+        // they can read or write this store, and untracked so their reads
+        // never leak into an effect that happens to be writing
+        let state = self.state.read().unwrap().clone();
+        let (subscribers, pruned) = self.live_subscribers();
+        // Dropped outside the lock, the callbacks may own other primitives
+        drop(pruned);
+        self.runtime.untracked(|| {
+            subscribers
+                .iter()
+                .fold(SubscriberResponse::Done, |response, subscriber| {
+                    response.merge(subscriber(&state))
+                })
+        })
+    }
+
+    /// Snapshot the callbacks to notify, taking out those whose token died.
+    fn live_subscribers(&self) -> (Vec<Subscriber<T>>, Vec<SubscriberEntry<T>>) {
+        let is_alive = |entry: &SubscriberEntry<T>| {
+            entry
+                .alive
+                .as_ref()
+                .is_none_or(|alive| alive.strong_count() > 0)
+        };
+        let mut subscribers = self.subscribers.write().unwrap();
+        let mut pruned = Vec::new();
+        if !subscribers.iter().all(is_alive) {
+            let (live, dead) = std::mem::take(&mut *subscribers)
+                .into_iter()
+                .partition(is_alive);
+            *subscribers = live;
+            pruned = dead;
         }
+        let callbacks = subscribers
+            .iter()
+            .map(|entry| Arc::clone(&entry.callback))
+            .collect();
+        (callbacks, pruned)
+    }
+
+    /// Export the current state, without subscribers or history.
+    pub fn export(&self) -> T {
+        self.state.read().unwrap().clone()
+    }
+
+    /// Import a state exported from another store.
+    ///
+    /// The state is committed like [`set`](Self::set), so this store's own
+    /// subscribers are kept and notified with the imported state.
+    pub fn import(&self, value: T) {
+        self.set(value);
     }
 
     /// Read state without triggering reactivity.
@@ -65,6 +806,61 @@ impl<T: Clone> Store<T> {
         let state = self.state.read().unwrap();
         f(&*state)
     }
+
+    /// Read state without cloning, tracking the store as a dependency.
+    ///
+    /// Like [`get`](Self::get), memos and effects calling this re-run when
+    /// the store changes. `f` must not write to this store.
+    pub fn read_tracked<F, R>(&self, f: F) -> R
+    where
+        F: FnOnce(&T) -> R,
+    {
+        self.runtime.track_read(self.id);
+        self.read(f)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<T: Clone + serde::Serialize + 'static> Store<T> {
+    /// Export the current state as JSON.
+    pub fn export_json(&self) -> serde_json::Result<String> {
+        self.read(serde_json::to_string)
+    }
+
+    /// Take a JSON snapshot of the current state, for persistence.
+    ///
+    /// Pair it with [`restore`](Self::restore) to load it back.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the state cannot be represented as JSON, such as a map with
+    /// non-string keys. Use [`export_json`](Self::export_json) to handle that
+    /// as an error instead.
+    pub fn snapshot(&self) -> String {
+        self.export_json()
+            .expect("store state cannot be serialized to JSON")
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<T: Clone + serde::de::DeserializeOwned + 'static> Store<T> {
+    /// Import a state from JSON, keeping this store's subscribers.
+    ///
+    /// The JSON is parsed in full before the state is replaced, so the store
+    /// is left untouched if it does not parse. On success subscribers are
+    /// notified once.
+    pub fn import_json(&self, json: &str) -> serde_json::Result<()> {
+        self.import(serde_json::from_str(json)?);
+        Ok(())
+    }
+
+    /// Restore a state from a JSON [`snapshot`](Self::snapshot).
+    ///
+    /// Like [`import_json`](Self::import_json), the state is only replaced,
+    /// and subscribers notified once, if the whole snapshot parses.
+    pub fn restore(&self, snapshot: &str) -> serde_json::Result<()> {
+        self.import_json(snapshot)
+    }
 }
 
 impl<T: Clone> Clone for Store<T> {
@@ -72,6 +868,12 @@ impl<T: Clone> Clone for Store<T> {
         Self {
             state: Arc::clone(&self.state),
             subscribers: Arc::clone(&self.subscribers),
+            timeline: Arc::clone(&self.timeline),
+            frame: Arc::clone(&self.frame),
+            frozen: Arc::clone(&self.frozen),
+            middleware: Arc::clone(&self.middleware),
+            id: self.id,
+            runtime: Arc::clone(&self.runtime),
         }
     }
 }
@@ -32,7 +32,7 @@ fn signal_with() {
 #[test]
 fn signal_map() {
     let count = Signal::new(5);
-    let (doubled, _guard) = count.map(|n| n * 2);
+    let doubled = count.map(|n| n * 2);
     assert_eq!(doubled.get(), 10);
 
     count.set(10);
@@ -169,3 +169,2912 @@ fn complex_reactive_chain() {
     input.set(5);
     assert_eq!(quadrupled.get(), 20);
 }
+
+#[test]
+fn runtime_dispose_tracked_ids() {
+    use tincan::runtime::ReactiveRuntime;
+
+    let runtime = ReactiveRuntime::current();
+    let shared = Signal::new(0);
+
+    let plugin_runs = Arc::new(AtomicUsize::new(0));
+    let host_runs = Arc::new(AtomicUsize::new(0));
+
+    let mut plugin_effects = Vec::new();
+    let ids = runtime.track_created(|| {
+        let local = Signal::new(0);
+        let runs = plugin_runs.clone();
+        let shared = shared.clone();
+        plugin_effects.push(Effect::new(move || {
+            let _ = shared.get() + local.get();
+            runs.fetch_add(1, Ordering::SeqCst);
+        }));
+    });
+    assert_eq!(ids.len(), 2);
+
+    let _host = Effect::new({
+        let shared = shared.clone();
+        let runs = host_runs.clone();
+        move || {
+            let _ = shared.get();
+            runs.fetch_add(1, Ordering::SeqCst);
+        }
+    });
+
+    shared.set(1);
+    assert_eq!(plugin_runs.load(Ordering::SeqCst), 2);
+    assert_eq!(host_runs.load(Ordering::SeqCst), 2);
+
+    runtime.dispose_ids(&ids);
+
+    shared.set(2);
+    assert_eq!(plugin_runs.load(Ordering::SeqCst), 2);
+    assert_eq!(host_runs.load(Ordering::SeqCst), 3);
+}
+
+#[cfg(feature = "metrics")]
+#[test]
+fn runtime_metrics() {
+    use tincan::runtime::ReactiveRuntime;
+
+    let runtime = ReactiveRuntime::current();
+    runtime.enable_metrics();
+
+    let a = Signal::new(0);
+    let b = Signal::new(0);
+    let effect = Effect::new({
+        let a = a.clone();
+        move || {
+            let _ = a.get();
+        }
+    });
+
+    for i in 1..=3 {
+        a.set(i);
+    }
+    for i in 1..=5 {
+        b.set(i);
+    }
+
+    let metrics = runtime.metrics();
+    assert_eq!(metrics.writes(a.id()), 3);
+    assert_eq!(metrics.writes(b.id()), 5);
+    assert_eq!(metrics.runs(effect.id()), 3);
+}
+
+#[test]
+fn store_subscribe_mut() {
+    let store = Store::new(0);
+    let total = Arc::new(AtomicUsize::new(0));
+
+    let mut sum = 0;
+    store.subscribe_mut({
+        let total = total.clone();
+        move |n: &usize| {
+            sum += *n;
+            total.store(sum, Ordering::SeqCst);
+        }
+    });
+
+    store.set(1);
+    store.set(2);
+    store.update(|n| *n += 3);
+    assert_eq!(total.load(Ordering::SeqCst), 1 + 2 + 5);
+}
+
+#[test]
+fn signal_watch_mut() {
+    let count = Signal::new(0);
+    let seen = Arc::new(std::sync::Mutex::new(Vec::new()));
+
+    let mut history = Vec::new();
+    let _guard = count.watch_mut({
+        let seen = seen.clone();
+        move |value| {
+            history.push(value);
+            *seen.lock().unwrap() = history.clone();
+        }
+    });
+
+    count.set(1);
+    count.set(2);
+    count.update(|n| *n += 10);
+    assert_eq!(*seen.lock().unwrap(), vec![0, 1, 2, 12]);
+}
+
+#[test]
+fn memo_new_stateful() {
+    let words = Signal::new(vec!["a", "b"]);
+    let allocations = Arc::new(AtomicUsize::new(0));
+
+    let joined = Memo::new_stateful(String::new(), {
+        let words = words.clone();
+        let allocations = allocations.clone();
+        move |buffer: &mut String| {
+            if buffer.capacity() == 0 {
+                allocations.fetch_add(1, Ordering::SeqCst);
+                buffer.reserve(64);
+            }
+            buffer.clear();
+            for word in words.get() {
+                buffer.push_str(word);
+            }
+            buffer.clone()
+        }
+    });
+
+    assert_eq!(joined.get(), "ab");
+
+    words.set(vec!["x", "y", "z"]);
+    assert_eq!(joined.get(), "xyz");
+
+    words.set(vec!["q"]);
+    assert_eq!(joined.get(), "q");
+    assert_eq!(allocations.load(Ordering::SeqCst), 1);
+}
+
+#[test]
+fn signal_when() {
+    let enabled = Signal::new(false);
+    let count = Signal::new(0);
+    let runs = Arc::new(AtomicUsize::new(0));
+
+    let _guard = enabled.when({
+        let count = count.clone();
+        let runs = runs.clone();
+        move || {
+            let count = count.clone();
+            let runs = runs.clone();
+            Effect::new(move || {
+                let _ = count.get();
+                runs.fetch_add(1, Ordering::SeqCst);
+            })
+        }
+    });
+
+    count.set(1);
+    assert_eq!(runs.load(Ordering::SeqCst), 0);
+
+    enabled.set(true);
+    assert_eq!(runs.load(Ordering::SeqCst), 1);
+    count.set(2);
+    assert_eq!(runs.load(Ordering::SeqCst), 2);
+
+    enabled.set(false);
+    count.set(3);
+    assert_eq!(runs.load(Ordering::SeqCst), 2);
+
+    enabled.set(true);
+    assert_eq!(runs.load(Ordering::SeqCst), 3);
+}
+
+#[test]
+fn store_update_in_batch() {
+    let store = Store::new(0);
+    let signal = Signal::new(0);
+    let runs = Arc::new(AtomicUsize::new(0));
+
+    let _effect = Effect::new({
+        let store = store.clone();
+        let signal = signal.clone();
+        let runs = runs.clone();
+        move || {
+            let _ = store.get() + signal.get();
+            runs.fetch_add(1, Ordering::SeqCst);
+        }
+    });
+    assert_eq!(runs.load(Ordering::SeqCst), 1);
+
+    tincan::runtime::batch(|| {
+        store.update(|n| *n += 1);
+        signal.set(1);
+        assert_eq!(runs.load(Ordering::SeqCst), 1);
+    });
+    assert_eq!(runs.load(Ordering::SeqCst), 2);
+}
+
+#[test]
+fn store_subscribers_coalesce_in_batch() {
+    let store = Store::new(0);
+    let seen = Arc::new(std::sync::Mutex::new(Vec::new()));
+    store.subscribe({
+        let seen = Arc::clone(&seen);
+        move |state| seen.lock().unwrap().push(*state)
+    });
+
+    tincan::runtime::batch(|| {
+        store.update(|n| *n += 1);
+        store.update(|n| *n += 1);
+        store.set(3);
+        assert!(seen.lock().unwrap().is_empty());
+    });
+    assert_eq!(*seen.lock().unwrap(), vec![3]);
+
+    // Outside a batch, subscribers still run on every write
+    store.set(4);
+    assert_eq!(*seen.lock().unwrap(), vec![3, 4]);
+}
+
+#[test]
+fn signal_map_tracked() {
+    let a = Signal::new(1);
+    let b = Signal::new(10);
+
+    let sum = a.map_tracked({
+        let b = b.clone();
+        move |x| x + b.get()
+    });
+    assert_eq!(sum.get(), 11);
+
+    a.set(2);
+    assert_eq!(sum.get(), 12);
+
+    b.set(20);
+    assert_eq!(sum.get(), 22);
+}
+
+#[test]
+fn runtime_capture_dependencies() {
+    use tincan::runtime::ReactiveRuntime;
+
+    let a = Signal::new(1);
+    let b = Signal::new(2);
+    let c = Signal::new(3);
+
+    let (sum, ids) = ReactiveRuntime::current().capture_dependencies(|| a.get() + c.get());
+    assert_eq!(sum, 4);
+
+    let mut expected = vec![a.id(), c.id()];
+    expected.sort_unstable();
+    assert_eq!(ids, expected);
+    assert!(!ids.contains(&b.id()));
+}
+
+#[test]
+fn store_subscribe_unique() {
+    let store = Store::new(0);
+    let counter = Arc::new(AtomicUsize::new(0));
+
+    for _ in 0..2 {
+        let counter = counter.clone();
+        store.subscribe_unique("plugin", move |_| {
+            counter.fetch_add(1, Ordering::SeqCst);
+        });
+    }
+
+    store.update(|n| *n += 1);
+    assert_eq!(counter.load(Ordering::SeqCst), 1);
+
+    store.update(|n| *n += 1);
+    assert_eq!(counter.load(Ordering::SeqCst), 2);
+}
+
+#[test]
+fn signal_map_fan_out() {
+    let source = Signal::new(1);
+
+    let mut derived: Vec<_> = (1..=5)
+        .map(|k| source.map_with_guard(move |n| n * k))
+        .collect();
+    source.set(2);
+    for (k, (signal, _guard)) in derived.iter().enumerate() {
+        assert_eq!(signal.get(), 2 * (k as i32 + 1));
+    }
+
+    // Dropped maps leave the fan-out without affecting the others
+    let (kept, _kept_guard) = derived.remove(4);
+    derived.truncate(2);
+    source.set(3);
+    assert_eq!(kept.get(), 15);
+    assert_eq!(derived[0].0.get(), 3);
+    assert_eq!(derived[1].0.get(), 6);
+}
+
+#[test]
+fn store_computed() {
+    let prices = Store::new(vec![10, 20]);
+    let discount = Store::new(5);
+    let computes = Arc::new(AtomicUsize::new(0));
+
+    let total = tincan::computed((prices.clone(), discount.clone()), {
+        let computes = computes.clone();
+        move |(prices, discount): (Vec<i32>, i32)| {
+            computes.fetch_add(1, Ordering::SeqCst);
+            prices.iter().sum::<i32>() - discount
+        }
+    });
+
+    assert_eq!(total.get(), 25);
+    assert_eq!(total.get(), 25);
+    assert_eq!(computes.load(Ordering::SeqCst), 1);
+
+    prices.update(|prices| prices.push(30));
+    assert_eq!(total.get(), 55);
+
+    discount.set(10);
+    assert_eq!(total.get(), 50);
+    assert_eq!(computes.load(Ordering::SeqCst), 3);
+}
+
+#[test]
+fn signal_history() {
+    let value = Signal::new(0);
+    let history = value.history(3);
+    assert_eq!(history.get(), [0]);
+
+    for n in 1..=4 {
+        value.set(n);
+    }
+    assert_eq!(history.get(), [2, 3, 4]);
+}
+
+#[test]
+fn runtime_strict_mode() {
+    use tincan::runtime::ReactiveRuntime;
+
+    ReactiveRuntime::set_strict(true);
+
+    let outside = std::panic::catch_unwind(|| Signal::new(0));
+    assert!(outside.is_err());
+
+    let inside = ReactiveRuntime::scope(|| {
+        let count = Signal::new(1);
+        count.update(|n| *n += 1);
+        count.get()
+    });
+    assert_eq!(inside, 2);
+
+    ReactiveRuntime::set_strict(false);
+    assert_eq!(Signal::new(3).get(), 3);
+}
+
+#[test]
+fn signal_map_controlled() {
+    let source = Signal::new(1);
+    let (doubled, propagation) = source.map_controlled(|n| n * 2);
+    assert_eq!(doubled.get(), 2);
+
+    propagation.pause();
+    assert!(propagation.is_paused());
+    source.set(2);
+    source.set(3);
+    assert_eq!(doubled.get(), 2);
+
+    let writes = Arc::new(AtomicUsize::new(0));
+    let _guard = doubled.watch({
+        let writes = Arc::clone(&writes);
+        move |_| {
+            writes.fetch_add(1, Ordering::SeqCst);
+        }
+    });
+    writes.store(0, Ordering::SeqCst);
+
+    propagation.resume();
+    assert!(!propagation.is_paused());
+    assert_eq!(doubled.get(), 6);
+    assert_eq!(writes.load(Ordering::SeqCst), 1);
+
+    source.set(4);
+    assert_eq!(doubled.get(), 8);
+}
+
+#[test]
+fn store_time_travel() {
+    let store = Store::time_travel(0, None);
+    let notified = Arc::new(AtomicUsize::new(0));
+    store.subscribe({
+        let notified = Arc::clone(&notified);
+        move |&state| notified.store(state, Ordering::SeqCst)
+    });
+
+    store.set(1);
+    store.set(2);
+    store.update(|n| *n += 1);
+    assert_eq!(store.revision(), 3);
+    assert_eq!(store.history(), [(0, 0), (1, 1), (2, 2), (3, 3)]);
+
+    assert!(store.goto(1));
+    assert_eq!(store.get(), 1);
+    assert_eq!(store.revision(), 1);
+    assert_eq!(notified.load(Ordering::SeqCst), 1);
+
+    // A new edit branches from the restored state
+    store.set(10);
+    assert_eq!(store.revision(), 4);
+    assert_eq!(store.history(), [(0, 0), (1, 1), (4, 10)]);
+    assert!(!store.goto(3));
+
+    let capped = Store::time_travel(0, Some(2));
+    capped.set(1);
+    capped.set(2);
+    assert_eq!(capped.history(), [(1, 1), (2, 2)]);
+    assert!(Store::new(0).history().is_empty());
+}
+
+#[test]
+fn signal_from_iter() {
+    let (signal, mut advance) = Signal::from_iter(vec![1, 2, 3]);
+    let seen = Arc::new(std::sync::Mutex::new(Vec::new()));
+    let _effect = Effect::new({
+        let signal = signal.clone();
+        let seen = Arc::clone(&seen);
+        move || seen.lock().unwrap().push(signal.get())
+    });
+
+    while advance() {}
+    assert!(!advance());
+    assert_eq!(*seen.lock().unwrap(), [1, 2, 3]);
+}
+
+#[test]
+fn effect_new_filtered() {
+    let tracked = Signal::new(0);
+    let ignored = Signal::new(0);
+    let runs = Arc::new(AtomicUsize::new(0));
+
+    let tracked_id = tracked.id();
+    let _effect = Effect::new_filtered(move |id| id == tracked_id, {
+        let tracked = tracked.clone();
+        let ignored = ignored.clone();
+        let runs = Arc::clone(&runs);
+        move || {
+            let _ = tracked.get() + ignored.get();
+            runs.fetch_add(1, Ordering::SeqCst);
+        }
+    });
+    assert_eq!(runs.load(Ordering::SeqCst), 1);
+
+    ignored.set(1);
+    assert_eq!(runs.load(Ordering::SeqCst), 1);
+
+    tracked.set(1);
+    assert_eq!(runs.load(Ordering::SeqCst), 2);
+}
+
+#[test]
+fn store_export_import() {
+    let source = Store::new(vec!["dark_mode".to_string()]);
+    let target = Store::new(Vec::new());
+    let received = Arc::new(std::sync::Mutex::new(Vec::new()));
+    target.subscribe({
+        let received = Arc::clone(&received);
+        move |flags: &Vec<String>| *received.lock().unwrap() = flags.clone()
+    });
+
+    target.import(source.export());
+    assert_eq!(target.get(), ["dark_mode"]);
+    assert_eq!(*received.lock().unwrap(), ["dark_mode"]);
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn store_export_import_json() {
+    let source = Store::new(vec![1, 2, 3]);
+    let target = Store::new(Vec::<i32>::new());
+    let notified = Arc::new(AtomicUsize::new(0));
+    target.subscribe({
+        let notified = Arc::clone(&notified);
+        move |_| {
+            notified.fetch_add(1, Ordering::SeqCst);
+        }
+    });
+
+    let json = source.export_json().unwrap();
+    assert_eq!(json, "[1,2,3]");
+    target.import_json(&json).unwrap();
+    assert_eq!(target.get(), [1, 2, 3]);
+    assert_eq!(notified.load(Ordering::SeqCst), 1);
+
+    assert!(target.import_json("not json").is_err());
+    assert_eq!(target.get(), [1, 2, 3]);
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn store_snapshot_restore_struct() {
+    #[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+    struct Settings {
+        theme: String,
+        volume: u8,
+    }
+
+    let saved = Store::new(Settings {
+        theme: "dark".to_string(),
+        volume: 7,
+    });
+    let restored = Store::new(Settings {
+        theme: "light".to_string(),
+        volume: 0,
+    });
+    let notified = Arc::new(AtomicUsize::new(0));
+    restored.subscribe({
+        let notified = Arc::clone(&notified);
+        move |_| {
+            notified.fetch_add(1, Ordering::SeqCst);
+        }
+    });
+
+    restored.restore(&saved.snapshot()).unwrap();
+    assert_eq!(restored.get(), saved.get());
+    assert_eq!(notified.load(Ordering::SeqCst), 1);
+
+    // A document missing a field is rejected without a partial overwrite
+    assert!(restored.restore(r#"{"theme":"blue"}"#).is_err());
+    assert_eq!(restored.get().theme, "dark");
+    assert_eq!(notified.load(Ordering::SeqCst), 1);
+}
+
+#[test]
+fn signal_map_fused() {
+    use tincan::runtime::ReactiveRuntime;
+
+    let source = Signal::new(1);
+    let mut fused = None;
+    let ids = ReactiveRuntime::current().track_created(|| {
+        fused = Some(source.map_fused(|n| n + 1).then(|n| n * 10).build());
+    });
+    // One derived signal and the single effect driving it
+    assert_eq!(ids.len(), 2);
+
+    let fused = fused.unwrap();
+    assert_eq!(fused.get(), 20);
+    source.set(4);
+    assert_eq!(fused.get(), 50);
+}
+
+#[test]
+fn effect_new_background() {
+    use tincan::runtime::ReactiveRuntime;
+
+    let count = Signal::new(0);
+    let seen = Arc::new(std::sync::Mutex::new(Vec::new()));
+    let _effect = Effect::new_background({
+        let count = count.clone();
+        let seen = Arc::clone(&seen);
+        move || {
+            let value = count.get();
+            seen.lock()
+                .unwrap()
+                .push((std::thread::current().id(), value));
+        }
+    });
+
+    for n in 1..=5 {
+        count.set(n);
+    }
+    ReactiveRuntime::current().settle();
+
+    let seen = seen.lock().unwrap();
+    assert_eq!(seen.last().map(|&(_, value)| value), Some(5));
+    let writer = std::thread::current().id();
+    assert!(seen.iter().all(|&(thread, _)| thread != writer));
+    assert!(seen.windows(2).all(|pair| pair[0].1 <= pair[1].1));
+}
+
+#[test]
+fn signal_with_capacity() {
+    let items: Signal<Vec<i32>> = Signal::vec_with_capacity(64);
+    assert!(items.with(|items| items.is_empty() && items.capacity() >= 64));
+
+    items.update(|items| items.extend(0..64));
+    assert!(items.with(|items| items.capacity()) >= 64);
+
+    let index: Signal<std::collections::HashMap<String, i32>> = Signal::map_with_capacity(32);
+    assert!(index.with(|index| index.capacity()) >= 32);
+}
+
+#[test]
+fn store_notify_manual() {
+    let store = Store::new(Arc::new(std::sync::Mutex::new(0)));
+    let seen = Arc::new(AtomicUsize::new(0));
+    store.subscribe({
+        let seen = Arc::clone(&seen);
+        move |state| seen.store(*state.lock().unwrap(), Ordering::SeqCst)
+    });
+
+    // Mutate behind the store's back, then tell subscribers to re-read
+    store.read(|state| *state.lock().unwrap() = 7);
+    assert_eq!(seen.load(Ordering::SeqCst), 0);
+
+    store.notify();
+    assert_eq!(seen.load(Ordering::SeqCst), 7);
+}
+
+#[test]
+fn signal_dyn_trait_object() {
+    use tincan::DynSignal;
+
+    trait Shape: Send + Sync {
+        fn area(&self) -> f64;
+    }
+    struct Square(f64);
+    struct Circle(f64);
+    impl Shape for Square {
+        fn area(&self) -> f64 {
+            self.0 * self.0
+        }
+    }
+    impl Shape for Circle {
+        fn area(&self) -> f64 {
+            3.0 * self.0 * self.0
+        }
+    }
+
+    let shape: DynSignal<dyn Shape> = Signal::new(Arc::new(Square(2.0)));
+    let label: DynSignal<dyn std::fmt::Display + Send + Sync> =
+        shape.map_tracked(|shape| Arc::new(shape.area()) as Arc<_>);
+
+    let areas = Arc::new(std::sync::Mutex::new(Vec::new()));
+    let _effect = Effect::new({
+        let shape = shape.clone();
+        let areas = Arc::clone(&areas);
+        move || areas.lock().unwrap().push(shape.get().area())
+    });
+
+    shape.set(Arc::new(Circle(1.0)));
+    assert_eq!(*areas.lock().unwrap(), [4.0, 3.0]);
+    assert_eq!(label.get().to_string(), "3");
+}
+
+#[test]
+fn runtime_scope_unwind() {
+    use std::panic::{catch_unwind, AssertUnwindSafe};
+    use tincan::runtime::ReactiveRuntime;
+
+    assert_eq!(ReactiveRuntime::scope_depth(), 0);
+
+    // A panic in nested scopes pops every entry it pushed
+    let result = catch_unwind(|| {
+        ReactiveRuntime::scope(|| ReactiveRuntime::scope(|| panic!("inner scope")))
+    });
+    assert!(result.is_err());
+    assert_eq!(ReactiveRuntime::scope_depth(), 0);
+    assert!(Arc::ptr_eq(
+        &ReactiveRuntime::current(),
+        &ReactiveRuntime::global()
+    ));
+
+    // Catching inside an outer runtime restores that runtime, not the global one
+    let outer = ReactiveRuntime::new();
+    outer.with_runtime(|| {
+        let inner = ReactiveRuntime::new();
+        let result = catch_unwind(AssertUnwindSafe(|| {
+            inner.with_runtime(|| {
+                assert_eq!(ReactiveRuntime::scope_depth(), 2);
+                ReactiveRuntime::scope(|| panic!("innermost scope"))
+            })
+        }));
+        assert!(result.is_err());
+        assert_eq!(ReactiveRuntime::scope_depth(), 1);
+        assert!(Arc::ptr_eq(&ReactiveRuntime::current(), &outer));
+    });
+    assert_eq!(ReactiveRuntime::scope_depth(), 0);
+}
+
+#[test]
+fn signal_bind() {
+    let model = Signal::new(String::from("model"));
+    let field = Signal::new(String::new());
+    let writes = Arc::new(AtomicUsize::new(0));
+    let _guard = field.watch({
+        let writes = Arc::clone(&writes);
+        move |_| {
+            writes.fetch_add(1, Ordering::SeqCst);
+        }
+    });
+
+    let binding = tincan::bind(&model, &field);
+    assert_eq!(field.get(), "model");
+
+    field.set("typed".into());
+    assert_eq!(model.get(), "typed");
+
+    model.set("reset".into());
+    assert_eq!(field.get(), "reset");
+    // Initial watch call, initial sync, then one write per change with no echoes
+    assert_eq!(writes.load(Ordering::SeqCst), 4);
+
+    drop(binding);
+    model.set("unbound".into());
+    assert_eq!(field.get(), "reset");
+}
+
+#[test]
+fn memo_deduped_barrier() {
+    let count = Signal::new(2);
+    let parity = Memo::new_deduped({
+        let count = count.clone();
+        move || count.get() % 2
+    });
+
+    let counter = || Arc::new(AtomicUsize::new(0));
+    let (left_runs, right_runs, effect_runs) = (counter(), counter(), counter());
+    let left = Memo::new({
+        let parity = parity.clone();
+        let runs = Arc::clone(&left_runs);
+        move || {
+            runs.fetch_add(1, Ordering::SeqCst);
+            parity.get() + 10
+        }
+    });
+    let right = Memo::new({
+        let parity = parity.clone();
+        let runs = Arc::clone(&right_runs);
+        move || {
+            runs.fetch_add(1, Ordering::SeqCst);
+            parity.get() + 20
+        }
+    });
+    let _effect = Effect::new({
+        let runs = Arc::clone(&effect_runs);
+        move || {
+            let _ = left.get() + right.get();
+            runs.fetch_add(1, Ordering::SeqCst);
+        }
+    });
+    let runs = || [&left_runs, &right_runs, &effect_runs].map(|runs| runs.load(Ordering::SeqCst));
+    assert_eq!(runs(), [1, 1, 1]);
+
+    // Parity is unchanged, so nothing downstream re-runs
+    count.set(4);
+    assert_eq!(parity.get(), 0);
+    assert_eq!(runs(), [1, 1, 1]);
+
+    count.set(5);
+    assert_eq!(parity.get(), 1);
+    assert_eq!(runs(), [2, 2, 2]);
+}
+
+#[test]
+fn signal_watch_cancellable() {
+    let count = Signal::new(0);
+    let seen = Arc::new(std::sync::Mutex::new(Vec::new()));
+    let _guard = count.watch_cancellable({
+        let seen = Arc::clone(&seen);
+        move |value, cancel| {
+            seen.lock().unwrap().push(value);
+            if value >= 2 {
+                cancel();
+            }
+        }
+    });
+
+    for n in 1..=4 {
+        count.set(n);
+    }
+    assert_eq!(*seen.lock().unwrap(), [0, 1, 2]);
+
+    // Cancelling on the first call stops the watcher before any change
+    let calls = Arc::new(AtomicUsize::new(0));
+    let _guard = count.watch_cancellable({
+        let calls = Arc::clone(&calls);
+        move |_, cancel| {
+            calls.fetch_add(1, Ordering::SeqCst);
+            cancel();
+        }
+    });
+    count.set(5);
+    assert_eq!(calls.load(Ordering::SeqCst), 1);
+}
+
+#[derive(Clone, Debug, PartialEq)]
+struct Item {
+    id: u32,
+    text: &'static str,
+}
+
+impl tincan::store::Keyed for Item {
+    type Key = u32;
+
+    fn key(&self) -> u32 {
+        self.id
+    }
+}
+
+fn item_store() -> (Store<Vec<Item>>, Arc<AtomicUsize>) {
+    let store = Store::new(vec![
+        Item { id: 1, text: "a" },
+        Item { id: 2, text: "b" },
+        Item { id: 3, text: "c" },
+    ]);
+    let notified = Arc::new(AtomicUsize::new(0));
+    store.subscribe({
+        let notified = Arc::clone(&notified);
+        move |_| {
+            notified.fetch_add(1, Ordering::SeqCst);
+        }
+    });
+    (store, notified)
+}
+
+fn item_ids(store: &Store<Vec<Item>>) -> Vec<u32> {
+    store.read(|items| items.iter().map(|item| item.id).collect())
+}
+
+#[test]
+fn store_insert_item() {
+    use tincan::store::ListChange;
+
+    let (store, notified) = item_store();
+    let change = store.insert_item(1, Item { id: 4, text: "d" });
+    assert_eq!(change, ListChange::Inserted { key: 4, index: 1 });
+    assert_eq!(item_ids(&store), [1, 4, 2, 3]);
+    assert_eq!(notified.load(Ordering::SeqCst), 1);
+
+    // An existing key is reconciled in place rather than duplicated
+    let change = store.insert_item(0, Item { id: 2, text: "B" });
+    assert_eq!(change, ListChange::Replaced { key: 2, index: 2 });
+    assert_eq!(item_ids(&store), [1, 4, 2, 3]);
+    assert_eq!(store.read(|items| items[2].text), "B");
+    assert_eq!(notified.load(Ordering::SeqCst), 2);
+}
+
+#[test]
+fn store_remove_item() {
+    use tincan::store::ListChange;
+
+    let (store, notified) = item_store();
+    assert_eq!(
+        store.remove_item(&2),
+        Some(ListChange::Removed { key: 2, index: 1 })
+    );
+    assert_eq!(item_ids(&store), [1, 3]);
+    assert_eq!(notified.load(Ordering::SeqCst), 1);
+
+    assert_eq!(store.remove_item(&9), None);
+    assert_eq!(item_ids(&store), [1, 3]);
+    assert_eq!(notified.load(Ordering::SeqCst), 1);
+}
+
+#[test]
+fn store_move_item() {
+    use tincan::store::ListChange;
+
+    let (store, notified) = item_store();
+    assert_eq!(
+        store.move_item(&1, 10),
+        Some(ListChange::Moved {
+            key: 1,
+            from: 0,
+            to: 2
+        })
+    );
+    assert_eq!(item_ids(&store), [2, 3, 1]);
+    assert_eq!(notified.load(Ordering::SeqCst), 1);
+
+    assert_eq!(store.move_item(&9, 0), None);
+    assert_eq!(item_ids(&store), [2, 3, 1]);
+    assert_eq!(notified.load(Ordering::SeqCst), 1);
+}
+
+#[test]
+fn runtime_settle() {
+    use tincan::runtime::ReactiveRuntime;
+
+    let runtime = ReactiveRuntime::new();
+    runtime.with_runtime(|| {
+        let count = Signal::new(0);
+        let doubled = Signal::new(0);
+        let _effect = Effect::new_background({
+            let count = count.clone();
+            let doubled = doubled.clone();
+            move || {
+                std::thread::sleep(std::time::Duration::from_millis(5));
+                doubled.set(count.get() * 2);
+            }
+        });
+
+        runtime.settle();
+        assert_eq!(doubled.get(), 0);
+
+        for n in 1..=3 {
+            count.set(n);
+        }
+        runtime.settle();
+        assert_eq!(doubled.get(), 6);
+    });
+}
+
+#[test]
+fn signal_map_scan() {
+    let level = Signal::new(0);
+    // Emit true only on the change that crosses the threshold upward
+    let crossed = level.map_scan(false, |above: &mut bool, &value| {
+        let was_above = std::mem::replace(above, value > 10);
+        *above && !was_above
+    });
+    assert!(!crossed.get());
+
+    let edges = Arc::new(std::sync::Mutex::new(Vec::new()));
+    let _guard = crossed.watch({
+        let edges = Arc::clone(&edges);
+        move |edge| edges.lock().unwrap().push(edge)
+    });
+
+    for value in [5, 12, 15, 3, 20] {
+        level.set(value);
+    }
+    assert_eq!(
+        *edges.lock().unwrap(),
+        [false, false, true, false, false, true]
+    );
+}
+
+#[test]
+fn store_new_named() {
+    use tincan::runtime::ReactiveRuntime;
+
+    let runtime = ReactiveRuntime::new();
+    runtime.with_runtime(|| {
+        #[cfg(feature = "metrics")]
+        runtime.enable_metrics();
+
+        let settings = Store::new_named(0, "settings");
+        let _effect = Effect::new({
+            let settings = settings.clone();
+            move || {
+                let _ = settings.get();
+            }
+        });
+        settings.set(1);
+
+        assert_eq!(runtime.name_of(settings.id()).as_deref(), Some("settings"));
+        let dump = runtime.dump_graph();
+        let line = dump
+            .lines()
+            .find(|line| line.starts_with(&format!("{} ", settings.id())))
+            .expect("store is in the graph dump");
+        assert!(line.contains("source \"settings\""));
+
+        #[cfg(feature = "metrics")]
+        assert_eq!(runtime.metrics().writes(settings.id()), 1);
+    });
+}
+
+#[test]
+fn reentrant_writes_do_not_deadlock() {
+    use std::sync::mpsc;
+    use std::time::Duration;
+
+    let (done, finished) = mpsc::channel();
+    std::thread::spawn(move || {
+        let source = Signal::new(0);
+        let relay = Signal::new(0);
+        let sink = Signal::new(0);
+        let store = Store::new(0);
+
+        // Each hop writes another signal from inside a notification wave
+        let _relay = Effect::new({
+            let (source, relay) = (source.clone(), relay.clone());
+            move || relay.set(source.get() + 1)
+        });
+        let _doubled = relay.map(|n| n * 2);
+        let _sink = Effect::new({
+            let (relay, sink, store) = (relay.clone(), sink.clone(), store.clone());
+            move || {
+                sink.set(relay.get());
+                store.set(relay.get());
+            }
+        });
+        // A subscriber that reads and writes its own store
+        store.subscribe({
+            let store = store.clone();
+            move |&value| {
+                if value % 2 == 0 && store.get() == value {
+                    store.update(|n| *n += 1);
+                }
+            }
+        });
+
+        let writers: Vec<_> = (0..4)
+            .map(|offset| {
+                let source = source.clone();
+                std::thread::spawn(move || {
+                    for n in 0..200 {
+                        source.set(n * 4 + offset);
+                    }
+                })
+            })
+            .collect();
+        for writer in writers {
+            writer.join().unwrap();
+        }
+        done.send(sink.get()).unwrap();
+    });
+
+    let sink = finished
+        .recv_timeout(Duration::from_secs(10))
+        .expect("reentrant writes deadlocked");
+    assert!(sink > 0);
+}
+
+#[test]
+fn effect_with_cleanup() {
+    let source = Signal::new(0);
+    let log = Arc::new(std::sync::Mutex::new(Vec::new()));
+
+    let effect = Effect::with_cleanup({
+        let source = source.clone();
+        let log = Arc::clone(&log);
+        move || {
+            let value = source.get();
+            log.lock().unwrap().push(format!("setup {value}"));
+            let log = Arc::clone(&log);
+            move || log.lock().unwrap().push(format!("cleanup {value}"))
+        }
+    });
+
+    source.set(1);
+    source.set(2);
+    drop(effect);
+    source.set(3);
+
+    assert_eq!(
+        *log.lock().unwrap(),
+        [
+            "setup 0",
+            "cleanup 0",
+            "setup 1",
+            "cleanup 1",
+            "setup 2",
+            "cleanup 2"
+        ]
+    );
+}
+
+#[test]
+fn signal_unzip() {
+    let pair = Signal::new((1, String::from("a")));
+    let (number, text) = pair.unzip();
+    assert_eq!((number.get(), text.get()), (1, "a".to_string()));
+
+    let text_writes = Arc::new(AtomicUsize::new(0));
+    let _guard = text.watch({
+        let text_writes = Arc::clone(&text_writes);
+        move |_| {
+            text_writes.fetch_add(1, Ordering::SeqCst);
+        }
+    });
+
+    // Only the number changes, so the text signal stays quiet
+    pair.update(|(n, _)| *n = 2);
+    assert_eq!(number.get(), 2);
+    assert_eq!(text_writes.load(Ordering::SeqCst), 1);
+
+    pair.set((3, "b".into()));
+    assert_eq!((number.get(), text.get()), (3, "b".to_string()));
+    assert_eq!(text_writes.load(Ordering::SeqCst), 2);
+}
+
+#[test]
+fn store_subscribe_coalesced() {
+    let store = Store::new(0);
+    let frames = Arc::new(std::sync::Mutex::new(Vec::new()));
+    store.subscribe_coalesced({
+        let frames = Arc::clone(&frames);
+        move |&state| frames.lock().unwrap().push(state)
+    });
+
+    for n in 1..=5 {
+        store.set(n);
+    }
+    assert!(frames.lock().unwrap().is_empty());
+
+    assert!(store.flush_frame());
+    assert_eq!(*frames.lock().unwrap(), [5]);
+
+    // Nothing changed since the last frame
+    assert!(!store.flush_frame());
+    assert_eq!(*frames.lock().unwrap(), [5]);
+}
+
+#[test]
+fn signal_map_weak() {
+    use tincan::runtime::ReactiveRuntime;
+
+    let runtime = ReactiveRuntime::new();
+    runtime.with_runtime(|| {
+        let baseline = runtime.stats().observers;
+
+        let source = Signal::new(2);
+        let squared = source.map_weak(|n| n * n);
+        assert_eq!(runtime.stats().observers, baseline + 1);
+
+        source.set(3);
+        assert_eq!(squared.get(), 9);
+
+        drop(source);
+        assert_eq!(runtime.stats().observers, baseline);
+        assert_eq!(squared.get(), 9);
+    });
+}
+
+#[test]
+fn signal_map_disposes_sink_on_drop() {
+    use tincan::runtime::{self, ReactiveRuntime};
+
+    let runtime = ReactiveRuntime::new();
+    runtime.with_runtime(|| {
+        let baseline = runtime::stats().observers;
+
+        let source = Signal::new(2);
+        let doubled = source.map(|n| n * 2);
+        // The sink plus the fan-out's watcher on the source
+        assert_eq!(runtime::stats().observers, baseline + 2);
+
+        // Dropping the map disposes its sink without another write
+        drop(doubled);
+        assert_eq!(runtime::stats().observers, baseline + 1);
+
+        // Dropping the source disposes the sinks of maps still alive
+        let tripled = source.map(|n| n * 3);
+        assert_eq!(runtime::stats().observers, baseline + 2);
+        drop(source);
+        assert_eq!(runtime::stats().observers, baseline);
+        assert_eq!(tripled.get(), 6);
+    });
+}
+
+#[test]
+fn runtime_register_equality() {
+    use tincan::runtime::ReactiveRuntime;
+
+    // Not PartialEq; two readings are the same if they round to the same value
+    #[derive(Clone)]
+    struct Reading(f64);
+
+    let runtime = ReactiveRuntime::new();
+    runtime.register_equality(|a: &Reading, b: &Reading| a.0.round() == b.0.round());
+    runtime.with_runtime(|| {
+        let reading = Signal::new(Reading(1.0));
+        let runs = Arc::new(AtomicUsize::new(0));
+        let _effect = Effect::new({
+            let reading = reading.clone();
+            let runs = Arc::clone(&runs);
+            move || {
+                let _ = reading.get();
+                runs.fetch_add(1, Ordering::SeqCst);
+            }
+        });
+
+        reading.set(Reading(1.2));
+        assert_eq!(runs.load(Ordering::SeqCst), 1);
+        assert_eq!(reading.get().0, 1.0);
+
+        reading.set(Reading(2.0));
+        assert_eq!(runs.load(Ordering::SeqCst), 2);
+    });
+}
+
+#[test]
+fn signal_dynamic_combine() {
+    use tincan::signal::DynamicCombine;
+
+    let first = Signal::new(1);
+    let combine = DynamicCombine::new();
+    combine.add(&first);
+    let combined = combine.signal();
+    assert_eq!(combined.get(), [1]);
+
+    let second = Signal::new(2);
+    combine.add(&second);
+    assert_eq!(combined.get(), [1, 2]);
+
+    second.set(20);
+    assert_eq!(combined.get(), [1, 20]);
+
+    assert!(combine.remove(second.id()));
+    assert!(!combine.remove(second.id()));
+    assert_eq!(combined.get(), [1]);
+
+    second.set(200);
+    first.set(10);
+    assert_eq!(combined.get(), [10]);
+}
+
+#[test]
+fn store_read_tracked() {
+    let store = Store::new(vec![1, 2, 3]);
+    let counter = || Arc::new(AtomicUsize::new(0));
+    let (tracked_runs, untracked_runs) = (counter(), counter());
+
+    let tracked = Memo::new({
+        let store = store.clone();
+        let runs = Arc::clone(&tracked_runs);
+        move || {
+            runs.fetch_add(1, Ordering::SeqCst);
+            store.read_tracked(|items| items.iter().sum::<i32>())
+        }
+    });
+    let untracked = Memo::new({
+        let store = store.clone();
+        let runs = Arc::clone(&untracked_runs);
+        move || {
+            runs.fetch_add(1, Ordering::SeqCst);
+            store.read(|items| items.iter().sum::<i32>())
+        }
+    });
+    assert_eq!((tracked.get(), untracked.get()), (6, 6));
+
+    store.update(|items| items.push(4));
+    assert_eq!(tracked.get(), 10);
+    assert_eq!(untracked.get(), 6);
+    assert_eq!(tracked_runs.load(Ordering::SeqCst), 2);
+    assert_eq!(untracked_runs.load(Ordering::SeqCst), 1);
+}
+
+#[test]
+fn signal_map_catching() {
+    use tincan::signal::MapError;
+
+    let input = Signal::new(4);
+    let root = input.map_catching(|&n: &i32| {
+        if n == 0 {
+            panic!("zero is not allowed");
+        }
+        if n < 0 {
+            return Err(format!("{n} is negative"));
+        }
+        Ok(f64::from(n).sqrt())
+    });
+    assert_eq!(root.get(), Ok(2.0));
+
+    input.set(-1);
+    assert_eq!(root.get(), Err(MapError::Failed("-1 is negative".into())));
+
+    input.set(0);
+    assert_eq!(
+        root.get(),
+        Err(MapError::Panic("zero is not allowed".into()))
+    );
+
+    // The driving effect survives the panic
+    input.set(9);
+    assert_eq!(root.get(), Ok(3.0));
+}
+
+#[test]
+fn runtime_observer_error_handler() {
+    use tincan::runtime::ReactiveRuntime;
+
+    let runtime = ReactiveRuntime::new();
+    let reported = Arc::new(std::sync::Mutex::new(Vec::new()));
+    runtime.set_observer_error_handler({
+        let reported = Arc::clone(&reported);
+        move |observer_id, payload| {
+            let message = payload.downcast_ref::<&str>().copied().unwrap_or("?");
+            reported
+                .lock()
+                .unwrap()
+                .push((observer_id, message.to_string()));
+        }
+    });
+
+    runtime.with_runtime(|| {
+        let count = Signal::new(0);
+        let healthy_calls = Arc::new(AtomicUsize::new(0));
+        let _failing = count.watch(|n| {
+            if n == 1 {
+                panic!("watcher failed");
+            }
+        });
+        let _healthy = count.watch({
+            let calls = Arc::clone(&healthy_calls);
+            move |_| {
+                calls.fetch_add(1, Ordering::SeqCst);
+            }
+        });
+
+        count.set(1);
+        count.set(2);
+        assert_eq!(healthy_calls.load(Ordering::SeqCst), 3);
+
+        let reported = reported.lock().unwrap();
+        assert_eq!(reported.len(), 1);
+        assert_eq!(reported[0].1, "watcher failed");
+    });
+}
+
+#[test]
+fn signal_filter() {
+    let input = Signal::new(4);
+    let even = input.filter(|n| n % 2 == 0);
+    assert_eq!(even.get(), 4);
+
+    input.set(7);
+    assert_eq!(even.get(), 4);
+    input.set(10);
+    assert_eq!(even.get(), 10);
+
+    // A failing initial value seeds `filter`, but not `filter_or`
+    let odd_start = Signal::new(3);
+    assert_eq!(odd_start.filter(|n| n % 2 == 0).get(), 3);
+    let fallback = odd_start.filter_or(0, |n| n % 2 == 0);
+    assert_eq!(fallback.get(), 0);
+    odd_start.set(8);
+    assert_eq!(fallback.get(), 8);
+}
+
+#[test]
+fn memo_sync_to_store() {
+    let count = Signal::new(2);
+    let doubled = Memo::new({
+        let count = count.clone();
+        move || count.get() * 2
+    });
+    let store = Store::new(0);
+
+    let guard = doubled.sync_to_store(&store);
+    assert_eq!(store.get(), 4);
+
+    count.set(5);
+    assert_eq!(store.get(), 10);
+
+    drop(guard);
+    count.set(7);
+    assert_eq!(store.get(), 10);
+    assert_eq!(doubled.get(), 14);
+}
+
+#[test]
+fn signal_filter_map() {
+    let input = Signal::new(String::from("x"));
+    let parsed = input.filter_map(|text| text.parse::<i32>().ok());
+    assert_eq!(parsed.get(), 0);
+
+    input.set("42".to_string());
+    assert_eq!(parsed.get(), 42);
+    input.set("4x".to_string());
+    assert_eq!(parsed.get(), 42);
+    input.set("7".to_string());
+    assert_eq!(parsed.get(), 7);
+}
+
+#[test]
+fn runtime_with_snapshot() {
+    use tincan::runtime::{self, ReactiveRuntime};
+
+    let runtime = ReactiveRuntime::new();
+    let (first, second) = runtime.with_runtime(|| (Signal::new(0), Signal::new(0)));
+
+    let writer = std::thread::spawn({
+        let runtime = Arc::clone(&runtime);
+        let (first, second) = (first.clone(), second.clone());
+        move || {
+            for i in 1..=2000 {
+                runtime.batch(|| {
+                    first.set(i);
+                    second.set(i);
+                });
+            }
+        }
+    });
+
+    let reader = std::thread::spawn({
+        let runtime = Arc::clone(&runtime);
+        move || loop {
+            let (a, b) =
+                runtime.with_runtime(|| runtime::with_snapshot(|| (first.get(), second.get())));
+            assert_eq!(a, b, "snapshot observed a torn pair");
+            if a == 2000 {
+                break;
+            }
+        }
+    });
+
+    writer.join().unwrap();
+    reader.join().unwrap();
+}
+
+#[test]
+fn signal_scan() {
+    let deposits = Signal::new(0);
+    let total = deposits.scan(100, |total, amount| total + amount);
+    assert_eq!(total.get(), 100);
+
+    deposits.set(10);
+    deposits.set(20);
+    deposits.set(-5);
+    assert_eq!(total.get(), 125);
+}
+
+#[test]
+fn store_subscribe_while() {
+    let store = Store::new(0);
+    let calls = Arc::new(AtomicUsize::new(0));
+    let token = Arc::new(());
+
+    store.subscribe_while(Arc::downgrade(&token), {
+        let calls = Arc::clone(&calls);
+        move |_| {
+            calls.fetch_add(1, Ordering::SeqCst);
+        }
+    });
+
+    store.set(1);
+    assert_eq!(calls.load(Ordering::SeqCst), 1);
+
+    drop(token);
+    store.set(2);
+    assert_eq!(calls.load(Ordering::SeqCst), 1);
+    // The pruned subscriber released its captured state
+    assert_eq!(Arc::strong_count(&calls), 1);
+}
+
+#[test]
+fn signal_combine3() {
+    use tincan::signal::{combine3, combine4};
+
+    let a = Signal::new(1);
+    let b = Signal::new("b".to_string());
+    let c = Signal::new(false);
+    let combined = combine3(a.clone(), b.clone(), c.clone());
+    assert_eq!(combined.get(), (1, "b".to_string(), false));
+
+    a.set(2);
+    assert_eq!(combined.get(), (2, "b".to_string(), false));
+    b.set("bb".to_string());
+    assert_eq!(combined.get(), (2, "bb".to_string(), false));
+    c.set(true);
+    assert_eq!(combined.get(), (2, "bb".to_string(), true));
+
+    let d = Signal::new(0.5);
+    let all = combine4(a.clone(), b, c, d.clone());
+    d.set(1.5);
+    a.set(3);
+    assert_eq!(all.get(), (3, "bb".to_string(), true, 1.5));
+}
+
+#[test]
+fn signal_ord_combinators() {
+    let a = Signal::new(3);
+    let b = Signal::new(5);
+    let greater = a.gt(&b);
+    let smaller = a.min_with(&b);
+    assert!(!greater.get());
+    assert_eq!(smaller.get(), 3);
+
+    a.set(8);
+    assert!(greater.get());
+    assert_eq!(smaller.get(), 5);
+    b.set(8);
+    assert!(!greater.get());
+    assert_eq!(smaller.get(), 8);
+
+    let clamped = a.clamp(0, 10);
+    assert_eq!(clamped.get(), 8);
+    a.set(10);
+    assert_eq!(clamped.get(), 10);
+    a.set(11);
+    assert_eq!(clamped.get(), 10);
+    a.set(0);
+    assert_eq!(clamped.get(), 0);
+    a.set(-1);
+    assert_eq!(clamped.get(), 0);
+}
+
+#[test]
+fn signal_set_if_changed() {
+    let source = Signal::new(1);
+    let calls = Arc::new(AtomicUsize::new(0));
+    let _guard = source.watch({
+        let calls = Arc::clone(&calls);
+        move |_| {
+            calls.fetch_add(1, Ordering::SeqCst);
+        }
+    });
+    assert_eq!(calls.load(Ordering::SeqCst), 1);
+
+    assert!(!source.set_if_changed(1));
+    assert_eq!(calls.load(Ordering::SeqCst), 1);
+    assert!(source.set_if_changed(2));
+    assert_eq!(calls.load(Ordering::SeqCst), 2);
+
+    // Plain `set` still notifies unconditionally
+    source.set(2);
+    assert_eq!(calls.load(Ordering::SeqCst), 3);
+}
+
+#[test]
+fn runtime_dependency_lookups() {
+    use tincan::runtime::ReactiveRuntime;
+
+    let runtime = ReactiveRuntime::new();
+    runtime.with_runtime(|| {
+        let a = Signal::new(1);
+        let b = Signal::new(2);
+        let both = Effect::new({
+            let (a, b) = (a.clone(), b.clone());
+            move || {
+                let _ = a.get() + b.get();
+            }
+        });
+        let only_b = Effect::new({
+            let b = b.clone();
+            move || {
+                let _ = b.get();
+            }
+        });
+
+        let mut expected = vec![a.id(), b.id()];
+        expected.sort_unstable();
+        assert_eq!(runtime.dependencies_of(both.id()), expected);
+        assert_eq!(runtime.dependencies_of(only_b.id()), vec![b.id()]);
+
+        assert_eq!(runtime.observers_of(a.id()), vec![both.id()]);
+        let mut expected = vec![both.id(), only_b.id()];
+        expected.sort_unstable();
+        assert_eq!(runtime.observers_of(b.id()), expected);
+        assert!(runtime.observers_of(usize::MAX).is_empty());
+    });
+}
+
+#[test]
+fn signal_peek() {
+    let a = Signal::new(1);
+    let b = Signal::new(10);
+    let runs = Arc::new(AtomicUsize::new(0));
+    let seen = Arc::new(AtomicUsize::new(0));
+
+    let _effect = Effect::new({
+        let (a, b) = (a.clone(), b.clone());
+        let (runs, seen) = (Arc::clone(&runs), Arc::clone(&seen));
+        move || {
+            runs.fetch_add(1, Ordering::SeqCst);
+            let sum = a.peek() + a.peek_with(|a| *a) + b.get();
+            seen.store(sum, Ordering::SeqCst);
+        }
+    });
+    assert_eq!(runs.load(Ordering::SeqCst), 1);
+
+    a.set(2);
+    assert_eq!(runs.load(Ordering::SeqCst), 1);
+
+    b.set(20);
+    assert_eq!(runs.load(Ordering::SeqCst), 2);
+    assert_eq!(seen.load(Ordering::SeqCst), 24);
+}
+
+#[test]
+fn signal_concurrent_readers_and_writers() {
+    // Runs against whichever storage is enabled, including `arc-swap`
+    let signal = Signal::new((0u64, 0u64));
+    let writers: Vec<_> = (0..2)
+        .map(|_| {
+            let signal = signal.clone();
+            std::thread::spawn(move || {
+                for _ in 0..500 {
+                    signal.update(|(a, b)| {
+                        *a += 1;
+                        *b += 2;
+                    });
+                }
+            })
+        })
+        .collect();
+    let readers: Vec<_> = (0..4)
+        .map(|_| {
+            let signal = signal.clone();
+            std::thread::spawn(move || {
+                let mut last = 0;
+                for _ in 0..2000 {
+                    let (a, b) = signal.get();
+                    assert_eq!(b, a * 2, "torn read");
+                    assert!(a >= last, "value went backwards");
+                    last = a;
+                }
+            })
+        })
+        .collect();
+
+    for handle in writers.into_iter().chain(readers) {
+        handle.join().unwrap();
+    }
+    assert_eq!(signal.get(), (1000, 2000));
+}
+
+#[test]
+fn runtime_untrack() {
+    use tincan::runtime::{self, ReactiveRuntime};
+
+    let rt = ReactiveRuntime::new();
+    rt.with_runtime(|| {
+        let tracked = Signal::new(1);
+        let hidden = Signal::new(2);
+        let hidden_memo = Memo::new({
+            let hidden = hidden.clone();
+            move || hidden.get() * 10
+        });
+        let runs = Arc::new(AtomicUsize::new(0));
+        let effect = Effect::new({
+            let (tracked, hidden) = (tracked.clone(), hidden.clone());
+            let runs = Arc::clone(&runs);
+            move || {
+                runs.fetch_add(1, Ordering::SeqCst);
+                let _ = tracked.get();
+                runtime::untrack(|| hidden.get() + hidden_memo.get());
+            }
+        });
+
+        assert_eq!(rt.dependencies_of(effect.id()), vec![tracked.id()]);
+        hidden.set(3);
+        assert_eq!(runs.load(Ordering::SeqCst), 1);
+        tracked.set(5);
+        assert_eq!(runs.load(Ordering::SeqCst), 2);
+    });
+}
+
+#[test]
+fn store_subscriber_responses() {
+    use tincan::store::SubscriberResponse;
+
+    let store = Store::new(0);
+    store.subscribe(|_| {});
+    store.subscribe_responding(|count| {
+        if count % 2 == 0 {
+            SubscriberResponse::NeedsRepaint
+        } else {
+            SubscriberResponse::Done
+        }
+    });
+    store.subscribe_responding(|_| SubscriberResponse::Done);
+
+    assert_eq!(store.set(1), SubscriberResponse::Done);
+    assert!(store.update(|count| *count += 1).needs_repaint());
+    assert!(!store.update(|count| *count += 1).needs_repaint());
+}
+
+#[test]
+fn effect_on_explicit_dependencies() {
+    let trigger = Signal::new(1);
+    let other = Signal::new(100);
+    let seen = Arc::new(std::sync::Mutex::new(Vec::new()));
+
+    let _effect = tincan::on(
+        {
+            let trigger = trigger.clone();
+            move || trigger.get()
+        },
+        {
+            let (other, seen) = (other.clone(), Arc::clone(&seen));
+            move |value| seen.lock().unwrap().push(value + other.get())
+        },
+    );
+    assert_eq!(*seen.lock().unwrap(), vec![101]);
+
+    // Reads inside the effect body are not dependencies
+    other.set(200);
+    assert_eq!(*seen.lock().unwrap(), vec![101]);
+    trigger.set(2);
+    assert_eq!(*seen.lock().unwrap(), vec![101, 202]);
+
+    let deferred_seen = Arc::new(std::sync::Mutex::new(Vec::new()));
+    let _deferred = tincan::on_deferred(
+        {
+            let trigger = trigger.clone();
+            move || trigger.get()
+        },
+        {
+            let seen = Arc::clone(&deferred_seen);
+            move |value| seen.lock().unwrap().push(value)
+        },
+    );
+    assert!(deferred_seen.lock().unwrap().is_empty());
+    trigger.set(3);
+    assert_eq!(*deferred_seen.lock().unwrap(), vec![3]);
+}
+
+#[test]
+fn signal_map_construction_is_untracked() {
+    use tincan::runtime::ReactiveRuntime;
+
+    let rt = ReactiveRuntime::new();
+    rt.with_runtime(|| {
+        let trigger = Signal::new(0);
+        let source = Signal::new(1);
+        let maps = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let outer = Effect::new({
+            let (trigger, source) = (trigger.clone(), source.clone());
+            let maps = Arc::clone(&maps);
+            move || {
+                let _ = trigger.get();
+                maps.lock().unwrap().push(source.map(|n| n * 2));
+            }
+        });
+
+        assert_eq!(rt.dependencies_of(outer.id()), vec![trigger.id()]);
+        source.set(5);
+        assert_eq!(maps.lock().unwrap().len(), 1);
+        assert_eq!(maps.lock().unwrap()[0].get(), 10);
+    });
+}
+
+#[test]
+fn effect_on_cleanup() {
+    let source = Signal::new(0);
+    let cleanups = Arc::new(AtomicUsize::new(0));
+
+    let effect = Effect::new({
+        let source = source.clone();
+        let cleanups = Arc::clone(&cleanups);
+        move || {
+            let _ = source.get();
+            let cleanups = Arc::clone(&cleanups);
+            tincan::on_cleanup(move || {
+                cleanups.fetch_add(1, Ordering::SeqCst);
+            });
+        }
+    });
+    assert_eq!(cleanups.load(Ordering::SeqCst), 0);
+
+    source.set(1);
+    source.set(2);
+    assert_eq!(cleanups.load(Ordering::SeqCst), 2);
+
+    drop(effect);
+    assert_eq!(cleanups.load(Ordering::SeqCst), 3);
+}
+
+#[test]
+fn event_sourced_store_replay() {
+    use tincan::store::EventSourcedStore;
+
+    #[derive(Clone, Debug, PartialEq)]
+    enum Action {
+        Add(i32),
+        Double,
+    }
+
+    let live = EventSourcedStore::new(1, |total: &mut i32, action: &Action| match action {
+        Action::Add(n) => *total += n,
+        Action::Double => *total *= 2,
+    });
+    live.dispatch(Action::Add(2));
+    live.dispatch(Action::Double);
+    live.dispatch(Action::Add(-1));
+    assert_eq!(live.store().get(), 5);
+    assert_eq!(
+        live.actions(),
+        vec![Action::Add(2), Action::Double, Action::Add(-1)]
+    );
+
+    let replayed = Store::new(live.replay(1));
+    assert_eq!(replayed.get(), live.store().get());
+    assert_eq!(live.replay(0), 3);
+}
+
+#[test]
+fn root_batch_runs_effect_once() {
+    let (a, b, c) = (Signal::new(1), Signal::new(2), Signal::new(3));
+    let runs = Arc::new(AtomicUsize::new(0));
+    let sum = Arc::new(AtomicUsize::new(0));
+    let _effect = Effect::new({
+        let (a, b, c) = (a.clone(), b.clone(), c.clone());
+        let (runs, sum) = (Arc::clone(&runs), Arc::clone(&sum));
+        move || {
+            runs.fetch_add(1, Ordering::SeqCst);
+            sum.store(a.get() + b.get() + c.get(), Ordering::SeqCst);
+        }
+    });
+
+    tincan::batch(|| {
+        a.set(10);
+        b.set(20);
+        c.set(30);
+    });
+    assert_eq!(runs.load(Ordering::SeqCst), 2);
+    assert_eq!(sum.load(Ordering::SeqCst), 60);
+}
+
+#[test]
+fn signal_watch_latest() {
+    use std::time::Duration;
+    use tincan::runtime::ReactiveRuntime;
+
+    let runtime = ReactiveRuntime::new();
+    runtime.with_runtime(|| {
+        let source = Signal::new(0);
+        let processed = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let _guard = source.watch_latest({
+            let processed = Arc::clone(&processed);
+            move |value| {
+                std::thread::sleep(Duration::from_millis(20));
+                processed.lock().unwrap().push(value);
+            }
+        });
+
+        for value in 1..=50 {
+            source.set(value);
+        }
+        runtime.settle();
+
+        let processed = processed.lock().unwrap();
+        assert_eq!(processed.last(), Some(&50));
+        assert!(processed.len() < 51, "no intermediate values were skipped");
+        assert!(processed.windows(2).all(|pair| pair[0] < pair[1]));
+    });
+}
+
+#[test]
+fn diamond_effect_runs_once() {
+    let a = Signal::new(1);
+    let b = Memo::new({
+        let a = a.clone();
+        move || a.get() * 2
+    });
+    let c = Memo::new({
+        let a = a.clone();
+        move || a.get() * 3
+    });
+    let seen = Arc::new(std::sync::Mutex::new(Vec::new()));
+    let _effect = Effect::new({
+        let seen = Arc::clone(&seen);
+        move || seen.lock().unwrap().push((b.get(), c.get()))
+    });
+
+    a.set(2);
+    a.set(3);
+    // One run per change, each seeing both memos from the same source value
+    assert_eq!(*seen.lock().unwrap(), vec![(2, 3), (4, 6), (6, 9)]);
+}
+
+#[test]
+fn memo_change_reruns_dependent_effect() {
+    let source = Signal::new(1);
+    let squared = Memo::new({
+        let source = source.clone();
+        move || source.get() * source.get()
+    });
+    let seen = Arc::new(std::sync::Mutex::new(Vec::new()));
+    let _effect = Effect::new({
+        let seen = Arc::clone(&seen);
+        move || seen.lock().unwrap().push(squared.get())
+    });
+
+    source.set(3);
+    source.set(4);
+    assert_eq!(*seen.lock().unwrap(), vec![1, 9, 16]);
+}
+
+#[test]
+fn signal_map_while() {
+    let source = Signal::new(1);
+    let scaled = source.map_while(|n| (*n >= 0).then(|| n * 10)).unwrap();
+    assert_eq!(scaled.get(), 10);
+
+    source.set(4);
+    assert_eq!(scaled.get(), 40);
+
+    // The first `None` freezes the derived signal for good
+    source.set(-1);
+    assert_eq!(scaled.get(), 40);
+    source.set(7);
+    assert_eq!(scaled.get(), 40);
+    assert!(tincan::runtime::ReactiveRuntime::current()
+        .observers_of(source.id())
+        .is_empty());
+
+    // Nothing to start from, so no derived signal and nothing left tracking
+    let negative = Signal::new(-5);
+    assert!(negative
+        .map_while(|n: &i32| (*n >= 0).then_some(*n))
+        .is_none());
+    assert!(tincan::runtime::ReactiveRuntime::current()
+        .observers_of(negative.id())
+        .is_empty());
+}
+
+#[test]
+fn memo_new_with_eq() {
+    let word = Signal::new("Hello".to_string());
+    // Case-insensitive: toggling the case yields an "equal" memo value
+    let normalized = Memo::new_with_eq(
+        {
+            let word = word.clone();
+            move || word.get()
+        },
+        |a: &String, b: &String| a.eq_ignore_ascii_case(b),
+    );
+    let runs = Arc::new(AtomicUsize::new(0));
+    let _effect = Effect::new({
+        let runs = Arc::clone(&runs);
+        move || {
+            let _ = normalized.get();
+            runs.fetch_add(1, Ordering::SeqCst);
+        }
+    });
+
+    word.set("HELLO".to_string());
+    word.set("hello".to_string());
+    assert_eq!(runs.load(Ordering::SeqCst), 1);
+
+    word.set("world".to_string());
+    assert_eq!(runs.load(Ordering::SeqCst), 2);
+}
+
+#[test]
+fn store_get_cow() {
+    let store = Store::new(vec![1, 2, 3]);
+    assert_eq!(store.get_cow().len(), 3);
+
+    store.update(|items| items.push(4));
+    {
+        let items = store.get_cow();
+        assert_eq!(*items, vec![1, 2, 3, 4]);
+    }
+    // The guard is released, so writing works again
+    store.set(Vec::new());
+    assert!(store.get_cow().is_empty());
+}
+
+#[test]
+fn resource_fetches_on_source_change() {
+    use tincan::resource::{BlockingSpawner, Resource};
+    use tincan::runtime::ReactiveRuntime;
+
+    let runtime = ReactiveRuntime::new();
+    runtime.with_runtime(|| {
+        let id = Signal::new(1);
+        let resource = Resource::new(
+            &id,
+            |id: i32| async move {
+                if id < 0 {
+                    Err(format!("no item {id}"))
+                } else {
+                    Ok(id * 100)
+                }
+            },
+            BlockingSpawner,
+        );
+
+        runtime.settle();
+        assert_eq!(resource.value().get(), Some(100));
+        assert!(!resource.loading().get());
+
+        id.set(2);
+        runtime.settle();
+        assert_eq!(resource.value().get(), Some(200));
+        assert_eq!(resource.error().get(), None);
+
+        // Errors keep the last loaded value
+        id.set(-3);
+        runtime.settle();
+        assert_eq!(resource.error().get(), Some("no item -3".to_string()));
+        assert_eq!(resource.value().get(), Some(200));
+        assert!(!resource.loading().get());
+    });
+}
+
+#[cfg(feature = "rayon")]
+#[test]
+fn signal_par_map_reduce() {
+    let numbers = Signal::new((1..=100u64).collect::<Vec<_>>());
+    let sum_of_squares = numbers.par_map_reduce(|n| n * n, |a, b| a + b, 0);
+    assert_eq!(sum_of_squares.get(), 338_350);
+
+    numbers.update(|numbers| numbers.push(101));
+    assert_eq!(sum_of_squares.get(), 338_350 + 101 * 101);
+    numbers.set(vec![3, 4]);
+    assert_eq!(sum_of_squares.get(), 25);
+}
+
+#[test]
+fn signal_debounce() {
+    use std::time::Duration;
+
+    let query = Signal::new(String::new());
+    let debounced = query.debounce(Duration::from_millis(50));
+    let seen = Arc::new(std::sync::Mutex::new(Vec::new()));
+    let _guard = debounced.watch({
+        let seen = Arc::clone(&seen);
+        move |value| seen.lock().unwrap().push(value)
+    });
+
+    for text in ["r", "ru", "rus", "rust"] {
+        query.set(text.to_string());
+    }
+    assert_eq!(debounced.get(), "");
+
+    std::thread::sleep(Duration::from_millis(300));
+    assert_eq!(debounced.get(), "rust");
+    // The initial value, then only the final value of the burst
+    assert_eq!(
+        *seen.lock().unwrap(),
+        vec!["".to_string(), "rust".to_string()]
+    );
+}
+
+#[test]
+fn effect_group_disposes_members() {
+    use tincan::runtime::{self, ReactiveRuntime};
+    use tincan::EffectGroup;
+
+    ReactiveRuntime::scope(|| {
+        let source = Signal::new(0);
+        let runs = Arc::new(AtomicUsize::new(0));
+        let group = EffectGroup::new();
+        for _ in 0..3 {
+            let (source, runs) = (source.clone(), Arc::clone(&runs));
+            group.effect(move || {
+                let _ = source.get();
+                runs.fetch_add(1, Ordering::SeqCst);
+            });
+        }
+        assert_eq!(group.len(), 3);
+        assert_eq!(runtime::stats().observers, 3);
+
+        drop(group);
+        assert_eq!(runtime::stats().observers, 0);
+        source.set(1);
+        assert_eq!(runs.load(Ordering::SeqCst), 3);
+    });
+}
+
+#[test]
+fn signal_throttle() {
+    use std::time::Duration;
+
+    let position = Signal::new(0);
+    let throttled = position.throttle(Duration::from_millis(100));
+    let seen = Arc::new(std::sync::Mutex::new(Vec::new()));
+    let _guard = throttled.watch({
+        let seen = Arc::clone(&seen);
+        move |value| seen.lock().unwrap().push(value)
+    });
+
+    // The leading value goes through at once, the rest wait for the window
+    position.set(1);
+    position.set(2);
+    position.set(3);
+    assert_eq!(throttled.get(), 1);
+
+    std::thread::sleep(Duration::from_millis(350));
+    assert_eq!(throttled.get(), 3);
+    assert_eq!(*seen.lock().unwrap(), vec![0, 1, 3]);
+}
+
+#[test]
+fn signal_sample_every() {
+    use std::time::Duration;
+
+    let temperature = Signal::new(21);
+    let sampled = temperature.sample_every(Duration::from_millis(20));
+    let samples = Arc::new(AtomicUsize::new(0));
+    let _guard = sampled.watch({
+        let samples = Arc::clone(&samples);
+        move |value| {
+            assert_eq!(value, 21);
+            samples.fetch_add(1, Ordering::SeqCst);
+        }
+    });
+
+    // The source never changes, yet samples keep arriving
+    std::thread::sleep(Duration::from_millis(200));
+    assert!(samples.load(Ordering::SeqCst) >= 3);
+}
+
+#[test]
+fn signal_read_write_split() {
+    use tincan::{create_signal, ReadSignal, WriteSignal};
+
+    let (count, set_count): (ReadSignal<i32>, WriteSignal<i32>) = create_signal(1);
+    let doubled = Memo::new({
+        let count = count.clone();
+        move || count.get() * 2
+    });
+    set_count.set(5);
+    assert_eq!(doubled.get(), 10);
+    set_count.update(|n| *n += 1);
+    assert_eq!(count.get(), 6);
+
+    // A full signal splits into halves sharing its value, and back
+    let signal = Signal::new("a".to_string());
+    let (read, write) = signal.clone().split();
+    write.set("b".to_string());
+    assert_eq!(read.get(), "b");
+    assert_eq!(signal.get(), "b");
+    assert_eq!(read.into_signal().id(), signal.id());
+}
+
+#[test]
+fn store_subscribe_filtered() {
+    let store = Store::new(0);
+    let seen = Arc::new(std::sync::Mutex::new(Vec::new()));
+    let subscription = store.subscribe_filtered(|count| *count > 100, {
+        let seen = Arc::clone(&seen);
+        move |count| seen.lock().unwrap().push(*count)
+    });
+
+    for value in [50, 150, 99, 101, 100, 200] {
+        store.set(value);
+    }
+    assert_eq!(*seen.lock().unwrap(), vec![150, 101, 200]);
+
+    // Dropping the guard unsubscribes
+    drop(subscription);
+    store.set(300);
+    assert_eq!(*seen.lock().unwrap(), vec![150, 101, 200]);
+}
+
+#[test]
+fn signal_update_checked() {
+    let source = Signal::new(vec![1, 2, 3]);
+    let runs = Arc::new(AtomicUsize::new(0));
+    let _effect = Effect::new({
+        let (source, runs) = (source.clone(), Arc::clone(&runs));
+        move || {
+            source.with(|_| ());
+            runs.fetch_add(1, Ordering::SeqCst);
+        }
+    });
+
+    // Sorting an already sorted vector changes nothing
+    assert!(!source.update_checked(|v| v.sort()));
+    assert_eq!(runs.load(Ordering::SeqCst), 1);
+
+    assert!(source.update_checked(|v| v.push(4)));
+    assert_eq!(runs.load(Ordering::SeqCst), 2);
+    assert_eq!(source.get(), vec![1, 2, 3, 4]);
+
+    // The dirty-flag variant trusts the closure's answer
+    assert!(!source.update_if_dirty(|v| {
+        v.retain(|&n| n < 10);
+        false
+    }));
+    assert_eq!(runs.load(Ordering::SeqCst), 2);
+    assert!(source.update_if_dirty(|v| {
+        v.clear();
+        true
+    }));
+    assert_eq!(runs.load(Ordering::SeqCst), 3);
+}
+
+#[test]
+fn store_subscribe_scoped() {
+    let store = Store::new(0);
+    let calls = Arc::new(AtomicUsize::new(0));
+    let subscription = store.subscribe_scoped({
+        let calls = Arc::clone(&calls);
+        move |_| {
+            calls.fetch_add(1, Ordering::SeqCst);
+        }
+    });
+
+    store.set(1);
+    assert_eq!(calls.load(Ordering::SeqCst), 1);
+
+    drop(subscription);
+    store.update(|n| *n += 1);
+    assert_eq!(calls.load(Ordering::SeqCst), 1);
+}
+
+#[test]
+fn runtime_spawn_scoped() {
+    use tincan::runtime::{run_in_current_runtime, ReactiveRuntime};
+
+    let rt = ReactiveRuntime::new();
+    rt.with_runtime(|| {
+        let handle = ReactiveRuntime::spawn_scoped({
+            let rt = Arc::clone(&rt);
+            move || {
+                assert!(Arc::ptr_eq(&ReactiveRuntime::current(), &rt));
+                let signal = Signal::new(1);
+                let doubled = Memo::new({
+                    let signal = signal.clone();
+                    move || signal.get() * 2
+                });
+                signal.set(4);
+                assert_eq!(doubled.get(), 8);
+                // The memo registered with the scoped runtime, not the global one
+                assert_eq!(rt.stats().memos, 1);
+                doubled
+            }
+        });
+        let doubled = handle.join().unwrap();
+        assert_eq!(doubled.get(), 8);
+
+        // A plain wrapper carries the runtime into any thread as well
+        let task = run_in_current_runtime({
+            let rt = Arc::clone(&rt);
+            move || Arc::ptr_eq(&ReactiveRuntime::current(), &rt)
+        });
+        assert!(std::thread::spawn(task).join().unwrap());
+    });
+}
+
+#[test]
+fn store_select() {
+    #[derive(Clone)]
+    struct AppState {
+        user: String,
+        clicks: u32,
+    }
+
+    let store = Store::new(AppState {
+        user: "ada".to_string(),
+        clicks: 0,
+    });
+    let user = store.select(|state| state.user.clone());
+    let runs = Arc::new(AtomicUsize::new(0));
+    let _effect = Effect::new({
+        let (user, runs) = (user.clone(), Arc::clone(&runs));
+        move || {
+            user.with(|_| ());
+            runs.fetch_add(1, Ordering::SeqCst);
+        }
+    });
+
+    // Unrelated fields leave the selection untouched
+    store.update(|state| state.clicks += 1);
+    store.update(|state| state.clicks += 1);
+    assert_eq!(runs.load(Ordering::SeqCst), 1);
+    assert_eq!(user.get(), "ada");
+
+    store.update(|state| state.user = "grace".to_string());
+    assert_eq!(runs.load(Ordering::SeqCst), 2);
+    assert_eq!(user.get(), "grace");
+}
+
+#[test]
+fn signal_map_manual() {
+    let external = Arc::new(AtomicUsize::new(10));
+    let source = Signal::new(1);
+    let (total, refresh) = source.map_manual({
+        let external = Arc::clone(&external);
+        move |n| *n as usize + external.load(Ordering::SeqCst)
+    });
+    assert_eq!(total.get(), 11);
+
+    // External state changes go unnoticed until refreshed
+    external.store(20, Ordering::SeqCst);
+    assert_eq!(total.get(), 11);
+    refresh();
+    assert_eq!(total.get(), 21);
+
+    // Source changes still propagate on their own
+    source.set(2);
+    assert_eq!(total.get(), 22);
+}
+
+#[test]
+fn store_with_reducer() {
+    enum Action {
+        Inc,
+        Dec,
+        Set(i32),
+    }
+
+    let counter = Store::with_reducer(0, |count: &i32, action: &Action| match action {
+        Action::Inc => count + 1,
+        Action::Dec => count - 1,
+        Action::Set(n) => *n,
+    });
+    let notified = Arc::new(AtomicUsize::new(0));
+    counter.subscribe({
+        let notified = Arc::clone(&notified);
+        move |_| {
+            notified.fetch_add(1, Ordering::SeqCst);
+        }
+    });
+
+    counter.dispatch(Action::Inc);
+    counter.dispatch(Action::Inc);
+    counter.dispatch(Action::Dec);
+    assert_eq!(counter.get(), 1);
+    counter.dispatch(Action::Set(10));
+    counter.dispatch(Action::Dec);
+    assert_eq!(counter.get(), 9);
+    assert_eq!(notified.load(Ordering::SeqCst), 5);
+
+    // Plain updates still work next to dispatching
+    counter.update(|count| *count *= 2);
+    assert_eq!(counter.store().get(), 18);
+}
+
+#[test]
+fn store_freeze() {
+    let store = Store::new(1);
+    let notified = Arc::new(AtomicUsize::new(0));
+    store.subscribe({
+        let notified = Arc::clone(&notified);
+        move |_| {
+            notified.fetch_add(1, Ordering::SeqCst);
+        }
+    });
+    store.set(2);
+    assert!(!store.is_frozen());
+
+    let writer = store.clone();
+    store.freeze();
+    assert!(writer.is_frozen());
+    writer.set(3);
+    writer.update(|n| *n += 10);
+    assert_eq!(store.get(), 2);
+    assert_eq!(notified.load(Ordering::SeqCst), 1);
+
+    // Reading and subscribing still work
+    store.subscribe(|_| {});
+    assert_eq!(store.read(|n| *n), 2);
+}
+
+#[test]
+fn store_undo_redo() {
+    let store = Store::with_history(0, 3);
+    let notified = Arc::new(AtomicUsize::new(0));
+    store.subscribe({
+        let notified = Arc::clone(&notified);
+        move |_| {
+            notified.fetch_add(1, Ordering::SeqCst);
+        }
+    });
+    assert!(!store.can_undo());
+
+    for n in 1..=4 {
+        store.set(n);
+    }
+    // Only three changes fit, so the initial state was evicted
+    assert!(store.undo() && store.undo() && store.undo());
+    assert_eq!(store.get(), 1);
+    assert!(!store.undo());
+    assert_eq!(notified.load(Ordering::SeqCst), 7);
+
+    assert!(store.redo());
+    assert_eq!(store.get(), 2);
+    assert!(store.can_redo());
+
+    // A new change after an undo truncates the redo stack
+    store.set(20);
+    assert!(!store.can_redo());
+    assert!(!store.redo());
+    assert!(store.undo());
+    assert_eq!(store.get(), 2);
+    assert!(store.redo());
+    assert_eq!(store.get(), 20);
+}
+
+#[test]
+fn signal_age() {
+    use std::time::Duration;
+
+    let signal = Signal::new(0);
+    std::thread::sleep(Duration::from_millis(50));
+    let age = signal.age();
+    assert!(age >= Duration::from_millis(50));
+    assert!(age < Duration::from_secs(5));
+
+    // Every write resets the age, including through clones
+    signal.clone().set(1);
+    assert!(signal.age() < Duration::from_millis(50));
+    std::thread::sleep(Duration::from_millis(20));
+    signal.update(|n| *n += 1);
+    assert!(signal.age() < Duration::from_millis(20));
+}
+
+#[test]
+fn store_set_if_changed() {
+    let store = Store::new(vec![1, 2]);
+    let notified = Arc::new(AtomicUsize::new(0));
+    store.subscribe({
+        let notified = Arc::clone(&notified);
+        move |_| {
+            notified.fetch_add(1, Ordering::SeqCst);
+        }
+    });
+
+    assert!(!store.set_if_changed(vec![1, 2]));
+    assert!(!store.set_if_changed(vec![1, 2]));
+    assert!(!store.update_if_changed(|items| items.sort()));
+    assert_eq!(notified.load(Ordering::SeqCst), 0);
+    assert_eq!(store.revision(), 0);
+
+    assert!(store.set_if_changed(vec![2, 1]));
+    assert!(store.update_if_changed(|items| items.sort()));
+    assert_eq!(notified.load(Ordering::SeqCst), 2);
+    assert_eq!(store.get(), vec![1, 2]);
+}
+
+#[test]
+fn memo_new_with_refresh() {
+    use std::time::Duration;
+
+    let source = Signal::new(1);
+    let computes = Arc::new(AtomicUsize::new(0));
+    let memo = Memo::new_with_refresh(Duration::from_millis(30), {
+        let (source, computes) = (source.clone(), Arc::clone(&computes));
+        move || {
+            computes.fetch_add(1, Ordering::SeqCst);
+            source.get() * 10
+        }
+    });
+    assert_eq!(memo.get(), 10);
+    assert_eq!(computes.load(Ordering::SeqCst), 1);
+
+    // Dependency changes still invalidate it
+    source.set(2);
+    assert_eq!(memo.get(), 20);
+    assert_eq!(computes.load(Ordering::SeqCst), 2);
+
+    // A tick invalidates it without any dependency changing
+    std::thread::sleep(Duration::from_millis(100));
+    assert_eq!(memo.get(), 20);
+    assert_eq!(computes.load(Ordering::SeqCst), 3);
+}
+
+#[test]
+fn store_middleware() {
+    let store = Store::new(5);
+    let notified = Arc::new(AtomicUsize::new(0));
+    store.subscribe({
+        let notified = Arc::clone(&notified);
+        move |_| {
+            notified.fetch_add(1, Ordering::SeqCst);
+        }
+    });
+    let logged = Arc::new(std::sync::Mutex::new(Vec::new()));
+    store.add_middleware(|_, next: &mut i32| *next >= 0);
+    store.add_middleware({
+        let logged = Arc::clone(&logged);
+        move |old, next| {
+            logged.lock().unwrap().push((*old, *next));
+            true
+        }
+    });
+
+    // Negative values are vetoed before the logger runs
+    store.update(|n| *n -= 10);
+    store.set(-1);
+    assert_eq!(store.get(), 5);
+    assert_eq!(notified.load(Ordering::SeqCst), 0);
+    assert!(logged.lock().unwrap().is_empty());
+
+    store.set(7);
+    assert_eq!(store.get(), 7);
+    assert_eq!(notified.load(Ordering::SeqCst), 1);
+    assert_eq!(*logged.lock().unwrap(), vec![(5, 7)]);
+
+    // Middlewares may also rewrite the proposed state
+    store.add_middleware(|_, next| {
+        *next = (*next).min(100);
+        true
+    });
+    store.set(500);
+    assert_eq!(store.get(), 100);
+}
+
+#[test]
+fn store_subscribe_with_action() {
+    #[derive(Clone, Debug, PartialEq)]
+    enum Action {
+        Add(i32),
+        Reset,
+    }
+
+    let counter = Store::with_reducer(0, |count: &i32, action: &Action| match action {
+        Action::Add(n) => count + n,
+        Action::Reset => 0,
+    });
+    let seen = Arc::new(std::sync::Mutex::new(Vec::new()));
+    counter.subscribe_with_action({
+        let seen = Arc::clone(&seen);
+        move |count: &i32, action: &Action| seen.lock().unwrap().push((action.clone(), *count))
+    });
+
+    counter.dispatch(Action::Add(3));
+    counter.dispatch(Action::Add(4));
+    counter.dispatch(Action::Reset);
+    // Direct writes carry no action
+    counter.set(9);
+    assert_eq!(
+        *seen.lock().unwrap(),
+        vec![(Action::Add(3), 3), (Action::Add(4), 7), (Action::Reset, 0)]
+    );
+}
+
+#[test]
+fn store_action_subscribers_in_batch_and_untracked() {
+    let counter = Store::with_reducer(0, |count: &i32, n: &i32| count + n);
+    let seen = Arc::new(std::sync::Mutex::new(Vec::new()));
+    counter.subscribe({
+        let seen = Arc::clone(&seen);
+        move |count: &i32| seen.lock().unwrap().push(format!("plain {count}"))
+    });
+    let other = Signal::new(0);
+    counter.subscribe_with_action({
+        let (seen, other) = (Arc::clone(&seen), other.clone());
+        move |count: &i32, n: &i32| {
+            let _ = other.get();
+            seen.lock().unwrap().push(format!("action {n} -> {count}"));
+        }
+    });
+
+    tincan::runtime::batch(|| {
+        counter.dispatch(1);
+        counter.dispatch(2);
+        assert!(seen.lock().unwrap().is_empty());
+    });
+    assert_eq!(
+        *seen.lock().unwrap(),
+        ["plain 3", "action 1 -> 1", "action 2 -> 3"]
+    );
+
+    // An action subscriber's reads never leak into the dispatching effect
+    let runs = Arc::new(AtomicUsize::new(0));
+    let _effect = Effect::new({
+        let (counter, runs) = (counter.clone(), Arc::clone(&runs));
+        move || {
+            runs.fetch_add(1, Ordering::SeqCst);
+            counter.dispatch(0);
+        }
+    });
+    other.set(1);
+    assert_eq!(runs.load(Ordering::SeqCst), 1);
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn signal_serde_round_trip() {
+    let tags = Signal::new(vec!["a".to_string(), "b".to_string()]);
+    let json = serde_json::to_string(&tags).unwrap();
+    assert_eq!(json, r#"["a","b"]"#);
+
+    // Deserializing creates a new, independent signal
+    let restored: Signal<Vec<String>> = serde_json::from_str(&json).unwrap();
+    assert_ne!(restored.id(), tags.id());
+    assert_eq!(restored.get(), tags.get());
+    tags.update(|tags| tags.push("c".to_string()));
+    assert_eq!(restored.get().len(), 2);
+}
+
+#[test]
+fn owner_dispose() {
+    use tincan::runtime::{ReactiveRuntime, RuntimeStats};
+    use tincan::Owner;
+
+    let rt = ReactiveRuntime::new();
+    rt.with_runtime(|| {
+        let owner = Owner::new();
+        let count = owner.signal(1);
+        let doubled = owner.memo({
+            let count = count.clone();
+            move || count.get() * 2
+        });
+        let runs = Arc::new(AtomicUsize::new(0));
+        owner.effect({
+            let (doubled, runs) = (doubled.clone(), Arc::clone(&runs));
+            move || {
+                let _ = doubled.get();
+                runs.fetch_add(1, Ordering::SeqCst);
+            }
+        });
+        assert_eq!(owner.len(), 3);
+        count.set(2);
+        assert_eq!(runs.load(Ordering::SeqCst), 2);
+        assert_eq!(rt.stats().observers, 1);
+        assert_eq!(rt.stats().memos, 1);
+
+        owner.dispose();
+        assert!(owner.is_empty());
+        assert_eq!(rt.stats(), RuntimeStats::default());
+
+        // The signal no longer drives anything
+        count.set(3);
+        assert_eq!(runs.load(Ordering::SeqCst), 2);
+    });
+}
+
+#[test]
+fn memo_recomputes_after_dirtying_pass() {
+    let source = Signal::new(1);
+    let plus_one = Memo::new({
+        let source = source.clone();
+        move || source.get() + 1
+    });
+    let doubled = Memo::new({
+        let source = source.clone();
+        move || source.get() * 2
+    });
+    let computed = Arc::new(std::sync::Mutex::new(Vec::new()));
+    let sum = Memo::new_deduped({
+        let (plus_one, doubled) = (plus_one.clone(), doubled.clone());
+        let computed = Arc::clone(&computed);
+        move || {
+            let sum = plus_one.get() + doubled.get();
+            computed.lock().unwrap().push(sum);
+            sum
+        }
+    });
+    let seen = Arc::new(std::sync::Mutex::new(Vec::new()));
+    let _effect = Effect::new({
+        let (sum, seen) = (sum.clone(), Arc::clone(&seen));
+        move || seen.lock().unwrap().push(sum.get())
+    });
+
+    // Both paths are invalidated before the deduplicating memo recomputes,
+    // so it never sees one updated input next to one stale input
+    source.set(5);
+    source.set(10);
+    assert_eq!(*computed.lock().unwrap(), vec![4, 16, 31]);
+    assert_eq!(*seen.lock().unwrap(), vec![4, 16, 31]);
+}
+
+#[test]
+fn signal_vec_helpers() {
+    use tincan::VecSignalExt;
+
+    let items = Signal::new(vec![1, 2, 3]);
+    let notified = Arc::new(AtomicUsize::new(0));
+    let _guard = items.watch({
+        let notified = Arc::clone(&notified);
+        move |_| {
+            notified.fetch_add(1, Ordering::SeqCst);
+        }
+    });
+    let notified = move || notified.load(Ordering::SeqCst);
+    assert_eq!(notified(), 1);
+
+    items.push(4);
+    assert_eq!(items.get(), [1, 2, 3, 4]);
+    assert_eq!(notified(), 2);
+
+    assert_eq!(items.pop(), Some(4));
+    assert_eq!(items.get(), [1, 2, 3]);
+    assert_eq!(notified(), 3);
+
+    assert_eq!(items.remove(0), Some(1));
+    assert_eq!(items.get(), [2, 3]);
+    assert_eq!(notified(), 4);
+
+    items.push(6);
+    items.retain(|n| n % 2 == 0);
+    assert_eq!(items.get(), [2, 6]);
+    assert_eq!(notified(), 6);
+
+    items.clear();
+    assert!(items.get().is_empty());
+    assert_eq!(notified(), 7);
+
+    // Every call notifies, like `update`, even when nothing changed
+    assert_eq!(items.remove(5), None);
+    assert_eq!(items.pop(), None);
+    items.clear();
+    items.retain(|_| true);
+    assert_eq!(notified(), 11);
+}
+
+#[test]
+fn signal_map_lazy() {
+    use tincan::runtime::ReactiveRuntime;
+
+    let rt = ReactiveRuntime::new();
+    rt.with_runtime(|| {
+        let source = Signal::new(1);
+        let computes = Arc::new(AtomicUsize::new(0));
+        let doubled = source.map_lazy({
+            let computes = Arc::clone(&computes);
+            move |n| {
+                computes.fetch_add(1, Ordering::SeqCst);
+                n * 2
+            }
+        });
+        let computes = move || computes.load(Ordering::SeqCst);
+        assert_eq!(computes(), 1);
+
+        // Unobserved, the source is not tracked and writes cost nothing
+        source.set(2);
+        assert!(rt.observers_of(source.id()).is_empty());
+        assert_eq!(computes(), 1);
+
+        // The first observer sees a caught-up value
+        let seen = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let effect = Effect::new({
+            let (doubled, seen) = (doubled.clone(), Arc::clone(&seen));
+            move || seen.lock().unwrap().push(doubled.get())
+        });
+        assert_eq!(*seen.lock().unwrap(), vec![4]);
+        assert_eq!(rt.observers_of(source.id()).len(), 1);
+        source.set(3);
+        assert_eq!(*seen.lock().unwrap(), vec![4, 6]);
+        // One catch-up and one change; the re-run kept the same driver
+        assert_eq!(computes(), 3);
+
+        // Dropping the last observer stops tracking
+        drop(effect);
+        assert!(rt.observers_of(source.id()).is_empty());
+        source.set(4);
+        assert_eq!(computes(), 3);
+
+        let _watch = doubled.watch(|_| {});
+        assert_eq!(doubled.get(), 8);
+    });
+}
+
+#[test]
+fn signal_replace_and_take() {
+    let buffer = Signal::new(vec![1, 2]);
+    let seen = Arc::new(std::sync::Mutex::new(Vec::new()));
+    let _guard = buffer.watch({
+        let seen = Arc::clone(&seen);
+        move |items| seen.lock().unwrap().push(items)
+    });
+
+    assert_eq!(buffer.replace(vec![3]), [1, 2]);
+    assert_eq!(buffer.get(), [3]);
+    assert_eq!(buffer.take(), [3]);
+    assert!(buffer.get().is_empty());
+    assert_eq!(*seen.lock().unwrap(), vec![vec![1, 2], vec![3], vec![]]);
+}
+
+#[test]
+fn signal_update_returning() {
+    let items = Signal::new(vec![1, 2]);
+    let notified = Arc::new(AtomicUsize::new(0));
+    let _guard = items.watch({
+        let notified = Arc::clone(&notified);
+        move |_| {
+            notified.fetch_add(1, Ordering::SeqCst);
+        }
+    });
+
+    let len = items.update_returning(|items| {
+        items.push(3);
+        items.len()
+    });
+    assert_eq!(len, 3);
+    assert_eq!(items.get(), [1, 2, 3]);
+    assert_eq!(notified.load(Ordering::SeqCst), 2);
+}
+
+#[test]
+fn store_try_update_versioned() {
+    use std::sync::Barrier;
+    use tincan::store::VersionedUpdateError;
+
+    let store = Store::new(0);
+    let start = store.revision();
+    let barrier = Arc::new(Barrier::new(2));
+    let handles: Vec<_> = (1..=2)
+        .map(|n| {
+            let (store, barrier) = (store.clone(), Arc::clone(&barrier));
+            std::thread::spawn(move || {
+                // Both writers prepared their change against the same revision
+                barrier.wait();
+                let first = store.try_update_versioned(start, |value| *value += n);
+                let retried = first.is_err().then(|| {
+                    let revision = store.revision();
+                    store.try_update_versioned(revision, |value| *value += n)
+                });
+                (first, retried)
+            })
+        })
+        .collect();
+    let results: Vec<_> = handles.into_iter().map(|h| h.join().unwrap()).collect();
+
+    let winners = results.iter().filter(|(first, _)| first.is_ok()).count();
+    assert_eq!(winners, 1);
+    for (first, retried) in &results {
+        if let Err(error) = first {
+            assert_eq!(
+                *error,
+                VersionedUpdateError::Conflict {
+                    expected: start,
+                    actual: start + 1
+                }
+            );
+            assert_eq!(*retried, Some(Ok(start + 2)));
+        }
+    }
+    assert_eq!(store.get(), 3);
+    assert_eq!(
+        store.try_update_versioned(start, |value| *value = 0),
+        Err(VersionedUpdateError::Conflict {
+            expected: start,
+            actual: start + 2
+        })
+    );
+}
+
+#[test]
+fn store_try_update_versioned_frozen_or_vetoed() {
+    use tincan::store::VersionedUpdateError;
+
+    let store = Store::new(0);
+    store.add_middleware(|_, next| *next >= 0);
+    let revision = store.revision();
+    assert_eq!(
+        store.try_update_versioned(revision, |value| *value = -1),
+        Err(VersionedUpdateError::Vetoed)
+    );
+
+    store.freeze();
+    assert_eq!(
+        store.try_update_versioned(revision, |value| *value = 1),
+        Err(VersionedUpdateError::Frozen)
+    );
+    assert_eq!(store.get(), 0);
+    assert_eq!(store.revision(), revision);
+}
+
+#[test]
+fn signal_map_with_triggers() {
+    let external = Arc::new(AtomicUsize::new(10));
+    let invalidated = Signal::new(());
+    let source = Signal::new(1);
+    let total = source.map_with_triggers(vec![invalidated.id()], {
+        let external = Arc::clone(&external);
+        move |n| *n as usize + external.load(Ordering::SeqCst)
+    });
+    assert_eq!(total.get(), 11);
+
+    // The trigger's value is never read, only its changes matter
+    external.store(20, Ordering::SeqCst);
+    assert_eq!(total.get(), 11);
+    invalidated.set(());
+    assert_eq!(total.get(), 21);
+
+    source.set(2);
+    assert_eq!(total.get(), 22);
+}
+
+#[test]
+fn event_sourced_store_skips_vetoed_actions() {
+    use tincan::store::EventSourcedStore;
+
+    let store = EventSourcedStore::new(0, |total: &mut i32, n: &i32| *total += n);
+    store.store().add_middleware(|_, total| *total >= 0);
+
+    store.dispatch(5);
+    store.dispatch(-10);
+    assert_eq!(store.store().get(), 5);
+    assert_eq!(store.actions(), vec![5]);
+    assert_eq!(store.replay(0), 5);
+}
+
+#[test]
+fn runtime_track_created_survives_panic() {
+    use std::panic::{catch_unwind, AssertUnwindSafe};
+    use tincan::runtime::ReactiveRuntime;
+
+    let runtime = ReactiveRuntime::current();
+    let mut inner = None;
+    let mut after = None;
+    let ids = runtime.track_created(|| {
+        let caught = catch_unwind(AssertUnwindSafe(|| {
+            runtime.track_created(|| {
+                inner = Some(Signal::new(1));
+                panic!("plugin failed");
+            })
+        }));
+        assert!(caught.is_err());
+        after = Some(Signal::new(2));
+    });
+
+    // The panicking frame was popped and its IDs still reached the outer one
+    assert_eq!(ids, vec![inner.unwrap().id(), after.unwrap().id()]);
+}
+
+#[test]
+fn store_undo_races_with_update() {
+    let store = Store::time_travel(0u64, None);
+    let writer = std::thread::spawn({
+        let store = store.clone();
+        move || {
+            for _ in 0..500 {
+                store.update(|n| *n += 1);
+            }
+        }
+    });
+    for _ in 0..500 {
+        store.undo();
+        // Holding the state, the current revision must name what it holds
+        store.read(|state| {
+            let revision = store.revision();
+            let recorded = store
+                .history()
+                .into_iter()
+                .find(|(rev, _)| *rev == revision)
+                .map(|(_, state)| state);
+            assert_eq!(recorded, Some(*state));
+        });
+    }
+    writer.join().unwrap();
+}